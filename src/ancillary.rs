@@ -0,0 +1,116 @@
+use std::ops::Range;
+
+use crate::types::Frame;
+use crate::utils::get_main_data_size;
+
+/// Ancillary data of a Layer III frame: the bytes between the end of its main data and
+/// the start of the main data of the next frame.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AncillaryData {
+    /// Byte ranges in the file, in order. Ancillary data can span several frames, so it
+    /// is split around their headers and side information.
+    pub ranges: Vec<Range<u32>>,
+    /// Number of bytes by which the main data of the next frame starts before the end
+    /// of the main data of this frame. It is 0 unless the side information is
+    /// inconsistent, in which case there is no ancillary data.
+    pub overlap: u32,
+}
+
+impl AncillaryData {
+    /// Length in bytes.
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|range| range.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copies the ancillary data out of `buf`, the content of the file.
+    pub fn bytes(&self, buf: &[u8]) -> Vec<u8> {
+        self.ranges
+            .iter()
+            .filter_map(|range| buf.get(range.start as usize..range.end as usize))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Whether the ancillary data starts with `signature`, to recognize the payload of
+    /// an encoder extension.
+    pub fn starts_with(&self, buf: &[u8], signature: &[u8]) -> bool {
+        self.bytes(buf).starts_with(signature)
+    }
+}
+
+/// Main data area of a frame, in the file and in the bit reservoir.
+struct Area {
+    file_start: u32,
+    reservoir_start: u64,
+    size: u32,
+}
+
+/// Maps a range of the bit reservoir to file ranges.
+fn to_file_ranges(areas: &[Area], range: Range<u64>) -> Vec<Range<u32>> {
+    areas
+        .iter()
+        .filter_map(|area| {
+            let start = range.start.max(area.reservoir_start);
+            let end = range.end.min(area.reservoir_start + area.size as u64);
+            if start < end {
+                let file_start = area.file_start + (start - area.reservoir_start) as u32;
+                Some(file_start..file_start + (end - start) as u32)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Locates the ancillary data of each frame. Every frame needs its side information.
+pub fn read_ancillary_data(frames: &[Frame]) -> Option<Vec<AncillaryData>> {
+    let mut areas = Vec::with_capacity(frames.len());
+    let mut reservoir_start = 0;
+    for frame in frames {
+        let size = get_main_data_size(frame);
+        areas.push(Area {
+            file_start: frame.offset + frame.size - size,
+            reservoir_start,
+            size,
+        });
+        reservoir_start += size as u64;
+    }
+    // Start and end of the main data of each frame, in the bit reservoir.
+    let mut main_data = Vec::with_capacity(frames.len());
+    for (frame, area) in frames.iter().zip(areas.iter()) {
+        let side_info = frame.side_info.as_ref()?;
+        let bits = side_info
+            .granules
+            .iter()
+            .flat_map(|granule| granule.iter())
+            .map(|granule| granule.part2_3_length as u64)
+            .sum::<u64>();
+        let start = area
+            .reservoir_start
+            .saturating_sub(side_info.main_data_begin as u64);
+        main_data.push(start..start + (bits + 7) / 8);
+    }
+    Some(
+        (0..frames.len())
+            .map(|index| {
+                let area = &areas[index];
+                let contiguous = frames
+                    .get(index + 1)
+                    .map(|next| frames[index].offset + frames[index].size == next.offset);
+                let end = match contiguous {
+                    Some(true) => main_data[index + 1].start,
+                    _ => area.reservoir_start + area.size as u64,
+                };
+                AncillaryData {
+                    ranges: to_file_ranges(&areas, main_data[index].end..end),
+                    overlap: main_data[index].end.saturating_sub(end) as u32,
+                }
+            })
+            .collect(),
+    )
+}
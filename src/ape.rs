@@ -0,0 +1,108 @@
+use crate::enums::ApeItemValue;
+use crate::types::{ApeItem, ApeTag};
+use crate::utils::create_utf8_str;
+
+pub const APE_HEADER_SIZE: usize = 32;
+
+const HAS_HEADER: u32 = 1 << 31;
+const IS_HEADER: u32 = 1 << 29;
+const READ_ONLY: u32 = 1;
+
+fn read_u32(buf: &[u8]) -> u32 {
+    u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]])
+}
+
+/// Header and footer of an APE tag share the same layout.
+pub struct ApeHeader {
+    pub version: u32,
+    /// Size of the items and of the footer, the header isn't included.
+    pub size: u32,
+    pub item_count: u32,
+    pub flags: u32,
+}
+
+impl ApeHeader {
+    pub fn read(buf: &[u8]) -> Option<ApeHeader> {
+        if buf.len() < APE_HEADER_SIZE || &buf[..8] != b"APETAGEX" {
+            return None;
+        }
+        let header = ApeHeader {
+            version: read_u32(&buf[8..]),
+            size: read_u32(&buf[12..]),
+            item_count: read_u32(&buf[16..]),
+            flags: read_u32(&buf[20..]),
+        };
+        if (header.size as usize) < APE_HEADER_SIZE {
+            return None;
+        }
+        Some(header)
+    }
+
+    pub fn is_header(&self) -> bool {
+        // APEv1 tags have no header and no flags.
+        self.version >= 2000 && self.flags & IS_HEADER != 0
+    }
+
+    pub fn has_header(&self) -> bool {
+        self.version >= 2000 && self.flags & HAS_HEADER != 0
+    }
+
+    /// Size of the whole tag, header included.
+    pub fn total_size(&self) -> usize {
+        self.size as usize
+            + if self.has_header() {
+                APE_HEADER_SIZE
+            } else {
+                0
+            }
+    }
+
+    /// Reads the items from `buf`, which starts right after the header (or where the
+    /// tag starts if there is no header) and ends before the footer.
+    pub fn read_tag(&self, buf: &[u8]) -> ApeTag {
+        let mut items = Vec::new();
+        let mut pos = 0;
+
+        for _ in 0..self.item_count {
+            if pos + 8 > buf.len() {
+                break;
+            }
+            let value_size = read_u32(&buf[pos..]) as usize;
+            let flags = read_u32(&buf[pos + 4..]);
+            pos += 8;
+            let key_len = match buf[pos..].iter().position(|c| *c == 0) {
+                Some(len) => len,
+                None => break,
+            };
+            let key = String::from_utf8_lossy(&buf[pos..pos + key_len]).into_owned();
+            pos += key_len + 1;
+            if value_size > buf.len() - pos {
+                break;
+            }
+            let value = &buf[pos..pos + value_size];
+            pos += value_size;
+
+            let (value, read_only) = if self.version < 2000 {
+                // APEv1 only has text items and no item flags.
+                (ApeItemValue::Text(create_utf8_str(value)), false)
+            } else {
+                let value = match (flags >> 1) & 3 {
+                    0 => ApeItemValue::Text(create_utf8_str(value)),
+                    2 => ApeItemValue::Locator(create_utf8_str(value)),
+                    _ => ApeItemValue::Binary(value.to_vec()),
+                };
+                (value, flags & READ_ONLY != 0)
+            };
+            items.push(ApeItem {
+                key,
+                value,
+                read_only,
+            });
+        }
+        ApeTag {
+            version: self.version,
+            read_only: self.version >= 2000 && self.flags & READ_ONLY != 0,
+            items,
+        }
+    }
+}
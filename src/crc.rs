@@ -0,0 +1,144 @@
+use crate::consts::LAYER2_ALLOCATION_BITS;
+use crate::enums::{ChannelType, Layer, ModeExtension, Version};
+use crate::types::Frame;
+use crate::utils::{get_side_info_size, BitReader};
+
+/// CRC-16 protecting MPEG audio frames (polynomial 0x8005, initial value 0xffff), updated
+/// with the first `bits` bits of `buf`.
+pub fn crc16_mpeg(mut crc: u16, buf: &[u8], bits: usize) -> u16 {
+    for n in 0..bits {
+        let bit = (buf[n / 8] >> (7 - n % 8)) & 1;
+        crc = if ((crc >> 15) as u8 ^ bit) != 0 {
+            (crc << 1) ^ 0x8005
+        } else {
+            crc << 1
+        };
+    }
+    crc
+}
+
+/// Number of bits following the checksum which are protected by it: the bit allocation
+/// for Layer I, the bit allocation and the scale factor selection information for
+/// Layer II and the side information for Layer III.
+fn get_protected_bits(buf: &[u8], frame: &Frame) -> Option<usize> {
+    let channels = match frame.chan_type {
+        ChannelType::SingleChannel => 1,
+        _ => 2,
+    };
+    // Subbands above the bound share the bit allocation of both channels in joint stereo.
+    let bound = |sblimit: usize| match frame.mode_extension {
+        Some(ModeExtension::IntensityBound(bound)) => sblimit.min(bound as usize),
+        _ => sblimit,
+    };
+    match frame.layer {
+        Layer::Layer1 => {
+            let bound = bound(32);
+            Some(4 * (channels * bound + 32 - bound))
+        }
+        Layer::Layer2 => {
+            let allocation_bits = LAYER2_ALLOCATION_BITS[get_layer2_table(frame, channels as u16)];
+            let sblimit = allocation_bits.len();
+            let bound = bound(sblimit);
+            let mut reader = BitReader::new(buf.get(6..)?);
+            let mut allocations = [[0; 32]; 2];
+            for (sb, bits) in allocation_bits.iter().enumerate() {
+                if sb < bound {
+                    for allocation in allocations.iter_mut().take(channels) {
+                        allocation[sb] = reader.read(*bits as u32)?;
+                    }
+                } else {
+                    let allocation = reader.read(*bits as u32)?;
+                    allocations[0][sb] = allocation;
+                    allocations[1][sb] = allocation;
+                }
+            }
+            for sb in 0..sblimit {
+                for allocation in allocations.iter().take(channels) {
+                    if allocation[sb] != 0 {
+                        reader.read(2)?;
+                    }
+                }
+            }
+            Some(reader.position())
+        }
+        Layer::Layer3 => Some(get_side_info_size(frame.version, frame.chan_type) * 8),
+        _ => None,
+    }
+}
+
+/// Index in `LAYER2_ALLOCATION_BITS` of the allocation table used by a Layer II frame.
+fn get_layer2_table(frame: &Frame, channels: u16) -> usize {
+    if frame.version != Version::MPEG1 {
+        return 4;
+    }
+    let bitrate = frame.bitrate / channels;
+    if (frame.sampling_freq == 48000 && bitrate >= 56) || (56..=80).contains(&bitrate) {
+        0
+    } else if frame.sampling_freq != 48000 && bitrate >= 96 {
+        1
+    } else if frame.sampling_freq != 32000 && bitrate <= 48 {
+        2
+    } else {
+        3
+    }
+}
+
+/// Checks the CRC of a protected frame. `buf` starts with the frame header. Returns
+/// `None` if the frame is too short to hold the protected data.
+pub fn check_frame_crc(buf: &[u8], frame: &Frame) -> Option<bool> {
+    let checksum = buf.get(4..6)?;
+    let bits = get_protected_bits(buf, frame)?;
+    let data = buf.get(6..6 + (bits + 7) / 8)?;
+    let crc = crc16_mpeg(0xffff, &buf[2..4], 16);
+    let crc = crc16_mpeg(crc, data, bits);
+    Some(crc == u16::from_be_bytes([checksum[0], checksum[1]]))
+}
+
+/// CRC-16 used by LAME for its tag (polynomial 0x8005, reflected, initial value 0).
+pub fn crc16_lame(buf: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for c in buf {
+        crc ^= *c as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xa001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_values() {
+        assert_eq!(crc16_lame(b"123456789"), 0xbb3d);
+        assert_eq!(crc16_mpeg(0xffff, b"123456789", 72), 0xaee7);
+    }
+
+    #[test]
+    fn layer2_protected_bits() {
+        // MPEG-1 Layer II, 128 kb/s, 44100 Hz, stereo: 27 subbands and nothing allocated.
+        let buf = [0; 64];
+        let frame = Frame {
+            version: Version::MPEG1,
+            layer: Layer::Layer2,
+            bitrate: 128,
+            sampling_freq: 44100,
+            chan_type: ChannelType::Stereo,
+            ..Default::default()
+        };
+        assert_eq!(get_protected_bits(&buf, &frame), Some(2 * 88));
+        let frame = Frame {
+            chan_type: ChannelType::JointStereo,
+            mode_extension: Some(ModeExtension::IntensityBound(4)),
+            ..frame
+        };
+        // Subbands from 4 upwards share their allocation.
+        assert_eq!(get_protected_bits(&buf, &frame), Some(88 + 16));
+    }
+}
@@ -2,23 +2,47 @@ use std::convert::From;
 use std::default::Default;
 use std::fmt;
 
+use crate::consts::{WINDOWS_1251, WINDOWS_1252};
+use crate::types::{
+    ApeTag, AudioTag, Frame, Lyrics3Tag, OptionalAudioTags, VbriHeader, XingHeader,
+};
+
+/// Kind of an [`Error`](crate::Error).
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum Error {
+pub enum ErrorKind {
+    /// The file couldn't be opened or read.
     FileError,
+    /// No frame was found.
     NotMP3,
     NoHeader,
     DuplicatedIDV3,
     InvalidData,
+    /// The content ended before the data being read.
+    Truncated,
+    /// Frame headers were found, but the following frames never confirmed them.
+    BadSync,
+    /// A tag header is inconsistent with the content of the tag.
+    BadTagHeader,
+    /// A tag uses a version which isn't supported.
+    UnsupportedVersion,
+    /// The content is too large for its offsets to fit in the `u32` of
+    /// [`Frame::offset`](crate::Frame::offset).
+    TooLarge,
 }
 
-impl fmt::Display for Error {
+impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let err = match *self {
-            Error::FileError => "An I/O error occurred",
-            Error::NotMP3 => "The file is not a valid MP3 file",
-            Error::NoHeader => "The file is missing an MP3 header",
-            Error::DuplicatedIDV3 => "The MP3 file contains a duplicate IDv3 frame",
-            Error::InvalidData => "The MP3 metadata is invalid",
+            ErrorKind::FileError => "An I/O error occurred",
+            ErrorKind::NotMP3 => "The file is not a valid MP3 file",
+            ErrorKind::NoHeader => "The file is missing an MP3 header",
+            ErrorKind::DuplicatedIDV3 => "The MP3 file contains a duplicate IDv3 frame",
+            ErrorKind::InvalidData => "The MP3 metadata is invalid",
+            ErrorKind::Truncated => "The MP3 file is truncated",
+            ErrorKind::BadSync => "No frame header could be confirmed by the following frames",
+            ErrorKind::BadTagHeader => "A tag header is invalid",
+            ErrorKind::UnsupportedVersion => "A tag version is not supported",
+            ErrorKind::TooLarge => "The MP3 file is too large",
         };
         err.fmt(f)
     }
@@ -130,6 +154,34 @@ impl From<u32> for ChannelType {
     }
 }
 
+/// Mode extension of a joint stereo frame, whose meaning depends on the layer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ModeExtension {
+    /// Layer I and II: subbands from this one upwards (4, 8, 12 or 16) are coded in
+    /// intensity stereo.
+    IntensityBound(u8),
+    /// Layer III: the stereo coding methods in use.
+    Layer3 {
+        intensity_stereo: bool,
+        ms_stereo: bool,
+    },
+}
+
+impl ModeExtension {
+    pub(crate) fn read(layer: Layer, bits: u32) -> Option<ModeExtension> {
+        match layer {
+            Layer::Layer1 | Layer::Layer2 => {
+                Some(ModeExtension::IntensityBound(4 * (bits as u8 + 1)))
+            }
+            Layer::Layer3 => Some(ModeExtension::Layer3 {
+                intensity_stereo: bits & 1 != 0,
+                ms_stereo: bits & 2 != 0,
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Copyright {
     None,
@@ -206,6 +258,214 @@ impl From<u32> for Emphasis {
     }
 }
 
+/// Single-byte codepage used to decode ID3v1 text fields.
+///
+/// The ID3v1 specification mandates ISO-8859-1 but a lot of taggers simply wrote
+/// the text using the local codepage of the machine.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Codepage {
+    /// ISO-8859-1, as required by the specification.
+    Latin1,
+    /// Windows-1251 (Cyrillic).
+    Windows1251,
+    /// Windows-1252 (Western European).
+    Windows1252,
+    /// Any other codepage: the table gives the characters for the bytes `0x80..=0xFF`,
+    /// the lower half being ASCII.
+    Custom(&'static [char; 128]),
+}
+
+impl Default for Codepage {
+    fn default() -> Codepage {
+        Codepage::Latin1
+    }
+}
+
+impl Codepage {
+    /// Returns the character encoded by the byte `c`.
+    pub fn decode(self, c: u8) -> char {
+        if c < 0x80 {
+            return c as char;
+        }
+        match self {
+            Codepage::Latin1 => c as char,
+            Codepage::Windows1251 => WINDOWS_1251[c as usize - 0x80],
+            Codepage::Windows1252 => WINDOWS_1252[c as usize - 0x80],
+            Codepage::Custom(table) => table[c as usize - 0x80],
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Lyrics3Version {
+    /// Only contains lyrics, ends with "LYRICSEND".
+    V1,
+    /// Made of fields, ends with "LYRICS200".
+    V2,
+}
+
+impl Default for Lyrics3Version {
+    fn default() -> Lyrics3Version {
+        Lyrics3Version::V2
+    }
+}
+
+/// Item found while walking through a file with a [`FrameIter`](crate::FrameIter), in
+/// the order of the file.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Event {
+    Frame(Frame),
+    Id3v1(AudioTag),
+    Id3v2(Box<OptionalAudioTags>),
+    Ape(ApeTag),
+    Lyrics3(Lyrics3Tag),
+}
+
+/// Value of an APE tag item.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ApeItemValue {
+    /// UTF-8 text. APEv2 uses NUL characters to separate multiple values.
+    Text(String),
+    /// Binary data, like a cover picture.
+    Binary(Vec<u8>),
+    /// UTF-8 link to an external resource.
+    Locator(String),
+}
+
+/// Window type of a Layer III granule.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BlockType {
+    /// Long blocks.
+    Normal,
+    /// Transition from long to short blocks.
+    Start,
+    /// Three short blocks.
+    Short,
+    /// Transition from short to long blocks.
+    Stop,
+}
+
+impl Default for BlockType {
+    fn default() -> BlockType {
+        BlockType::Normal
+    }
+}
+
+impl From<u32> for BlockType {
+    fn from(c: u32) -> BlockType {
+        match c {
+            0x0 => BlockType::Normal,
+            0x1 => BlockType::Start,
+            0x2 => BlockType::Short,
+            0x3 => BlockType::Stop,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Where the encoder delay and padding were read from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GaplessSource {
+    /// LAME tag, after the Xing header.
+    Lame,
+    /// "iTunSMPB" ID3v2 comment.
+    ITunSMPB,
+}
+
+/// How the bitrate varies along the stream.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BitrateMode {
+    /// Constant bitrate.
+    Cbr,
+    /// Variable bitrate.
+    Vbr,
+    /// Average bitrate: variable, but targeting a given average. Only reported by the
+    /// LAME tag.
+    Abr,
+    /// No frame was found.
+    Unknown,
+}
+
+impl Default for BitrateMode {
+    fn default() -> BitrateMode {
+        BitrateMode::Unknown
+    }
+}
+
+/// How much a [`DurationEstimate`](crate::DurationEstimate) can be trusted.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Confidence {
+    /// Computed from the number of frames stored in a Xing or VBRI header, or from all
+    /// the frames of a short stream.
+    High,
+    /// Computed from the bitrate, shared by the first frames: the stream is most likely
+    /// CBR.
+    Medium,
+    /// Computed from the bitrate of the first frame, while the following ones have a
+    /// different one: the stream is VBR without header.
+    Low,
+}
+
+/// How serious a [`Diagnostic`](crate::Diagnostic) is.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Severity {
+    /// The data is unusual but was read anyway.
+    Warning,
+    /// The parse would have failed if it wasn't lenient.
+    Error,
+}
+
+/// Encoding method stored in the LAME tag.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LameVbrMethod {
+    Unknown,
+    Cbr,
+    Abr,
+    /// `--vbr-old`
+    VbrOld,
+    /// `--vbr-mtrh`
+    VbrMtrh,
+    /// `--vbr-mt`
+    VbrMt,
+    /// `--vbr-new` (LAME 3.98 and later).
+    VbrNew,
+    Cbr2Pass,
+    Abr2Pass,
+    Reserved(u8),
+}
+
+impl Default for LameVbrMethod {
+    fn default() -> LameVbrMethod {
+        LameVbrMethod::Unknown
+    }
+}
+
+impl From<u8> for LameVbrMethod {
+    fn from(c: u8) -> LameVbrMethod {
+        match c {
+            0 => LameVbrMethod::Unknown,
+            1 => LameVbrMethod::Cbr,
+            2 => LameVbrMethod::Abr,
+            3 => LameVbrMethod::VbrOld,
+            4 => LameVbrMethod::VbrMtrh,
+            5 => LameVbrMethod::VbrMt,
+            6 => LameVbrMethod::VbrNew,
+            8 => LameVbrMethod::Cbr2Pass,
+            9 => LameVbrMethod::Abr2Pass,
+            c => LameVbrMethod::Reserved(c),
+        }
+    }
+}
+
+/// Kind of VBR header found in the first frame.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VbrHeaderKind {
+    /// "Xing" or "Info" header.
+    Xing(XingHeader),
+    /// Fraunhofer "VBRI" header.
+    Vbri(VbriHeader),
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Genre {
     Blues,
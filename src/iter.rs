@@ -0,0 +1,75 @@
+use std::io::{Read, Seek};
+
+use crate::enums::Event;
+use crate::metadata::Parser;
+use crate::options::ParseOptions;
+use crate::source::{ReaderSource, SliceSource};
+use crate::types::Error;
+
+/// Iterator over the frames and tags of an MP3 file held in memory, returned one by one
+/// as they are found instead of being collected in an [`MP3Metadata`](crate::MP3Metadata).
+///
+/// Tags stored at the end of the file are returned after the last frame. Frames are
+/// returned with a delay of one frame, since a following ID3v1 tag can still change the
+/// size of the last one. After an error, the iterator ends.
+///
+/// ```
+/// use mp3_metadata::{Event, FrameIter};
+///
+/// let buf = std::fs::read("assets/test.mp3").unwrap();
+/// let mut samples = 0;
+/// for event in FrameIter::new(&buf) {
+///     if let Event::Frame(frame) = event.unwrap() {
+///         samples = frame.first_sample;
+///     }
+/// }
+/// assert!(samples > 0);
+/// ```
+pub struct FrameIter<'a> {
+    parser: Parser<SliceSource<'a>>,
+}
+
+impl<'a> FrameIter<'a> {
+    pub fn new(buf: &'a [u8]) -> FrameIter<'a> {
+        FrameIter::with_options(buf, &ParseOptions::default())
+    }
+
+    pub fn with_options(buf: &'a [u8], options: &ParseOptions) -> FrameIter<'a> {
+        FrameIter {
+            parser: Parser::new(SliceSource::new(buf), options),
+        }
+    }
+}
+
+impl<'a> Iterator for FrameIter<'a> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Result<Event, Error>> {
+        self.parser.next()
+    }
+}
+
+/// Same as [`FrameIter`], reading the content from `reader` as it goes.
+pub struct ReaderFrameIter<R> {
+    parser: Parser<ReaderSource<R>>,
+}
+
+impl<R: Read + Seek> ReaderFrameIter<R> {
+    pub fn new(reader: R) -> Result<ReaderFrameIter<R>, Error> {
+        ReaderFrameIter::with_options(reader, &ParseOptions::default())
+    }
+
+    pub fn with_options(reader: R, options: &ParseOptions) -> Result<ReaderFrameIter<R>, Error> {
+        Ok(ReaderFrameIter {
+            parser: Parser::new(ReaderSource::new(reader)?, options),
+        })
+    }
+}
+
+impl<R: Read + Seek> Iterator for ReaderFrameIter<R> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Result<Event, Error>> {
+        self.parser.next()
+    }
+}
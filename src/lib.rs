@@ -1,9 +1,41 @@
-pub use enums::{ChannelType, Copyright, Emphasis, Error, Genre, Layer, Status, Version, CRC};
-pub use metadata::{read_from_file, read_from_slice};
-pub use types::{AudioTag, Frame, MP3Metadata, OptionalAudioTags, Url};
+pub use ancillary::AncillaryData;
+pub use enums::{
+    ApeItemValue, BitrateMode, BlockType, ChannelType, Codepage, Confidence, Copyright, Emphasis,
+    ErrorKind, Event, GaplessSource, Genre, LameVbrMethod, Layer, Lyrics3Version, ModeExtension,
+    Severity, Status, VbrHeaderKind, Version, CRC,
+};
+pub use iter::{FrameIter, ReaderFrameIter};
+pub use metadata::{
+    estimate_duration_from_file, estimate_duration_from_file_with, estimate_duration_from_reader,
+    estimate_duration_from_reader_with, estimate_duration_from_slice,
+    estimate_duration_from_slice_with, read_from_file, read_from_file_lenient,
+    read_from_file_lenient_with, read_from_file_with, read_from_reader, read_from_reader_lenient,
+    read_from_reader_lenient_with, read_from_reader_with, read_from_slice, read_from_slice_lenient,
+    read_from_slice_lenient_with, read_from_slice_with, read_tags_from_file,
+    read_tags_from_file_with, read_tags_from_reader, read_tags_from_reader_with,
+    read_tags_from_slice, read_tags_from_slice_with,
+};
+pub use options::ParseOptions;
+pub use seek::{SeekIndex, SeekResult};
+pub use types::{
+    ApeItem, ApeTag, AudioSummary, AudioTag, Comment, Diagnostic, DurationEstimate, Error, Frame,
+    GaplessInfo, Granule, LameTag, Lyrics3Tag, MP3Metadata, MP3Tags, MlltReference,
+    MpegLocationLookupTable, OptionalAudioTags, ReplayGain, ReservoirDependency, Segment, SideInfo,
+    Url, VbrHeader, VbriHeader, XingHeader,
+};
 
+mod ancillary;
+mod ape;
 mod consts;
+mod crc;
 mod enums;
+mod iter;
+mod lyrics3;
 mod metadata;
+mod options;
+mod seek;
+mod side_info;
+mod source;
 mod types;
 mod utils;
+mod vbr;
@@ -0,0 +1,90 @@
+use crate::enums::{Codepage, Lyrics3Version};
+use crate::types::Lyrics3Tag;
+
+const BEGIN: &[u8] = b"LYRICSBEGIN";
+/// Lyrics3 v1 tags can't be bigger than this (lyrics included).
+const V1_MAX_SIZE: usize = 5100;
+
+fn decode(buf: &[u8], codepage: Codepage) -> String {
+    buf.iter().map(|c| codepage.decode(*c)).collect()
+}
+
+fn parse_size(buf: &[u8]) -> Option<usize> {
+    if buf.is_empty() || !buf.iter().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(
+        buf.iter()
+            .fold(0, |size, c| size * 10 + (c - b'0') as usize),
+    )
+}
+
+/// Returns the largest number of bytes the Lyrics3 tag ending with `footer` (the 15 bytes
+/// before the end of the tag) can take.
+pub fn get_lyrics3_max_size(footer: &[u8]) -> Option<usize> {
+    if footer.len() < 15 {
+        return None;
+    }
+    match &footer[6..15] {
+        b"LYRICS200" => Some(15 + parse_size(&footer[..6])?),
+        b"LYRICSEND" => Some(V1_MAX_SIZE),
+        _ => None,
+    }
+}
+
+/// Looks for a Lyrics3 tag ending at `end` and returns it alongside the position
+/// where it starts.
+pub fn read_lyrics3(buf: &[u8], end: usize, codepage: Codepage) -> Option<(Lyrics3Tag, usize)> {
+    if end < BEGIN.len() + 9 {
+        return None;
+    }
+    match &buf[end - 9..end] {
+        b"LYRICS200" => {
+            // The size field covers everything from "LYRICSBEGIN" to the size field.
+            let size = parse_size(&buf[end.checked_sub(15)?..end - 9])?;
+            let start = end.checked_sub(15 + size)?;
+            let content = buf[start..end - 15].strip_prefix(BEGIN)?;
+            Some((read_v2_fields(content, codepage)?, start))
+        }
+        b"LYRICSEND" => {
+            let lower = end.saturating_sub(V1_MAX_SIZE);
+            let start = (lower..end - 9 - BEGIN.len())
+                .rev()
+                .find(|pos| buf[*pos..].starts_with(BEGIN))?;
+            let tag = Lyrics3Tag {
+                version: Lyrics3Version::V1,
+                lyrics: Some(decode(&buf[start + BEGIN.len()..end - 9], codepage)),
+                ..Lyrics3Tag::default()
+            };
+            Some((tag, start))
+        }
+        _ => None,
+    }
+}
+
+fn read_v2_fields(mut buf: &[u8], codepage: Codepage) -> Option<Lyrics3Tag> {
+    let mut tag = Lyrics3Tag {
+        version: Lyrics3Version::V2,
+        ..Lyrics3Tag::default()
+    };
+    while buf.len() >= 8 {
+        let size = parse_size(&buf[3..8])?;
+        if size > buf.len() - 8 {
+            return None;
+        }
+        let value = Some(decode(&buf[8..8 + size], codepage));
+        match &buf[..3] {
+            b"LYR" => tag.lyrics = value,
+            b"INF" => tag.additional_info = value,
+            b"AUT" => tag.author = value,
+            b"EAL" => tag.album = value,
+            b"EAR" => tag.artist = value,
+            b"ETT" => tag.title = value,
+            b"IMG" => tag.images = value,
+            b"IND" => tag.indications = value,
+            _ => {}
+        }
+        buf = &buf[8 + size..];
+    }
+    Some(tag)
+}
@@ -1,54 +1,362 @@
+use std::collections::VecDeque;
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Seek};
 use std::path::Path;
 use std::time::Duration;
 
+use crate::ape::{ApeHeader, APE_HEADER_SIZE};
 use crate::consts::{BITRATES, SAMPLING_FREQ};
-use crate::enums::{ChannelType, Copyright, Emphasis, Error, Genre, Layer, Status, Version, CRC};
-use crate::types::{AudioTag, Frame, MP3Metadata, OptionalAudioTags};
+use crate::crc::check_frame_crc;
+use crate::enums::{
+    ChannelType, Codepage, Confidence, Copyright, Emphasis, ErrorKind, Event, Genre, Layer,
+    ModeExtension, Severity, Status, Version, CRC,
+};
+use crate::lyrics3::{get_lyrics3_max_size, read_lyrics3};
+use crate::options::ParseOptions;
+use crate::side_info::read_side_info;
+use crate::source::{ReaderSource, SliceSource, Source};
+use crate::types::{
+    AudioSummary, AudioTag, Diagnostic, DurationEstimate, Error, Frame, MP3Metadata, MP3Tags,
+    OptionalAudioTags, Segment, VbrHeader,
+};
 use crate::utils::{
-    compute_duration, create_utf8_str, get_line, get_samp_line, get_text_field, get_text_fields,
+    compute_duration, create_id3v1_str, get_bitrate, get_comment, get_frame_count, get_frame_size,
+    get_line, get_mllt, get_samp_line, get_samples_per_frame, get_slot_size, get_text_field,
+    get_text_fields,
 };
 use crate::utils::{get_url_field, get_url_fields};
+use crate::vbr::read_vbr_header;
+
+fn read_id3v1(buf: &[u8], ext: Option<&[u8]>, codepage: Codepage) -> AudioTag {
+    // The "TAG+" block holds the next 60 characters of the title, artist and album.
+    let field = |v1: &[u8], ext: Option<&[u8]>| match ext {
+        Some(ext) => create_id3v1_str(&[v1, ext].concat(), codepage),
+        None => create_id3v1_str(v1, codepage),
+    };
+    let optional_field = |buf: &[u8]| {
+        let s = create_id3v1_str(buf, codepage);
+        if s.is_empty() {
+            None
+        } else {
+            Some(s)
+        }
+    };
+    // ID3v1.1 steals the last two bytes of the comment: a NUL followed by the track
+    // number.
+    let (comment_len, track) = if buf[125] == 0 && buf[126] != 0 {
+        (28, Some(buf[126]))
+    } else {
+        (30, None)
+    };
+    AudioTag {
+        title: field(&buf[3..][..30], ext.map(|ext| &ext[4..][..60])),
+        artist: field(&buf[33..][..30], ext.map(|ext| &ext[64..][..60])),
+        album: field(&buf[63..][..30], ext.map(|ext| &ext[124..][..60])),
+        year: create_id3v1_str(&buf[93..][..4], codepage)
+            .parse::<u16>()
+            .unwrap_or(0),
+        comment: create_id3v1_str(&buf[97..][..comment_len], codepage),
+        track,
+        genre: Genre::from(buf[127]),
+        speed: ext.and_then(|ext| if ext[184] != 0 { Some(ext[184]) } else { None }),
+        extended_genre: ext.and_then(|ext| optional_field(&ext[185..][..30])),
+        start_time: ext.and_then(|ext| optional_field(&ext[215..][..6])),
+        end_time: ext.and_then(|ext| optional_field(&ext[221..][..6])),
+    }
+}
+
+/// Reads `size` bytes at `pos`, or returns `None` if the content is shorter.
+fn read_exact_at<S: Source>(
+    source: &mut S,
+    pos: u64,
+    size: usize,
+) -> Result<Option<Vec<u8>>, Error> {
+    Ok(source
+        .read_at(pos, size)?
+        .get(..size)
+        .map(|buf| buf.to_vec()))
+}
+
+/// Largest content whose positions fit in a `u32`, with room for the size of an ID3v2 tag
+/// (at most 2^28 bytes) found near its end.
+const MAX_SIZE: u64 = (u32::MAX - (1 << 29)) as u64;
+
+/// Reads the tags stored at the end of the file and returns where the audio ends.
+fn read_trailing_tags<S: Source>(
+    source: &mut S,
+    state: &mut ParseState,
+    options: &ParseOptions,
+) -> Result<u64, Error> {
+    let mut end = source.len();
+    if end > MAX_SIZE {
+        return Err(Error::new(ErrorKind::TooLarge).with_offset(MAX_SIZE));
+    }
+    let mut has_lyrics3 = false;
+    if end >= 128 {
+        if let Some(v1) = read_exact_at(source, end - 128, 128)?.filter(|v1| v1.starts_with(b"TAG"))
+        {
+            end -= 128;
+            let ext = if end >= 227 {
+                read_exact_at(source, end - 227, 227)?.filter(|ext| ext.starts_with(b"TAG+"))
+            } else {
+                None
+            };
+            if ext.is_some() {
+                end -= 227;
+            }
+            state.has_id3v1 = true;
+            if options.id3v1 {
+                state.trailing.push(Event::Id3v1(read_id3v1(
+                    &v1,
+                    ext.as_deref(),
+                    options.codepage,
+                )));
+            }
+        }
+    }
+    // APE, Lyrics3 and appended ID3v2 tags can come in any order.
+    loop {
+        if end >= 20 {
+            let footer =
+                read_exact_at(source, end - 10, 10)?.filter(|footer| footer.starts_with(b"3DI"));
+            if let Some(footer) = footer {
+                // Header and footer included.
+                let size = footer[6..10]
+                    .iter()
+                    .fold(0, |size, c| (size << 7) | (*c as u64 & 0x7f))
+                    + 20;
+                if size <= end && !(options.id3v2 && options.accepts_tag_size(size)) {
+                    end -= size;
+                    continue;
+                }
+                if size <= end {
+                    let buf = read_exact_at(source, end - size, size as usize)?
+                        .filter(|buf| buf.starts_with(b"ID3"));
+                    if let Some(buf) = buf {
+                        let mut undecodable = Vec::new();
+                        let tag = read_appended_id3v2(&buf, &mut undecodable)
+                            .map_err(|e| e.with_offset(end - size).with_context("ID3v2 tag"));
+                        if let Some(tag) = state.recover(tag)?.flatten() {
+                            state.trailing.push(Event::Id3v2(Box::new(tag)));
+                        }
+                        state.report_undecodable(end - size, undecodable);
+                        end -= size;
+                        continue;
+                    }
+                }
+            }
+        }
+        if !state.has_ape && end >= APE_HEADER_SIZE as u64 {
+            let footer = read_exact_at(source, end - APE_HEADER_SIZE as u64, APE_HEADER_SIZE)?
+                .and_then(|footer| ApeHeader::read(&footer));
+            if let Some(footer) = footer {
+                let total_size = footer.total_size() as u64;
+                if !footer.is_header()
+                    && total_size <= end
+                    && !(options.ape && options.accepts_tag_size(total_size))
+                {
+                    state.has_ape = true;
+                    end -= total_size;
+                    continue;
+                }
+                if !footer.is_header() && total_size <= end {
+                    let items = end - footer.size as u64;
+                    let size = footer.size as usize - APE_HEADER_SIZE;
+                    if let Some(items) = read_exact_at(source, items, size)? {
+                        state.has_ape = true;
+                        state.trailing.push(Event::Ape(footer.read_tag(&items)));
+                        end -= total_size;
+                        continue;
+                    }
+                }
+            }
+        }
+        // Lyrics3 tags can only be found with an ID3v1 tag.
+        if !has_lyrics3 && state.has_id3v1 && end >= 15 {
+            let footer = read_exact_at(source, end - 15, 15)?;
+            let max_size = footer
+                .as_ref()
+                .and_then(|footer| get_lyrics3_max_size(footer));
+            if let (Some(footer), Some(max_size)) = (footer, max_size) {
+                let start = end.saturating_sub(max_size as u64);
+                // The size of a Lyrics3v2 tag is exact, the one of a Lyrics3v1 tag isn't.
+                if footer.ends_with(b"LYRICS200")
+                    && start + max_size as u64 == end
+                    && !options.accepts_tag_size(max_size as u64)
+                {
+                    has_lyrics3 = true;
+                    end = start;
+                    continue;
+                }
+                let size = (end - start) as usize;
+                let buf = if options.accepts_tag_size(size as u64) {
+                    read_exact_at(source, start, size)?
+                } else {
+                    None
+                };
+                if let Some(buf) = buf {
+                    if let Some((tag, tag_start)) = read_lyrics3(&buf, size, options.codepage) {
+                        has_lyrics3 = true;
+                        if options.lyrics3 {
+                            state.trailing.push(Event::Lyrics3(tag));
+                        }
+                        end = start + tag_start as u64;
+                        continue;
+                    }
+                }
+            }
+        }
+        // The tags were found from the end.
+        state.trailing.reverse();
+        return Ok(end);
+    }
+}
 
-fn get_id3(i: &mut u32, buf: &[u8], meta: &mut MP3Metadata) -> Result<(), Error> {
-    let mut x = *i as usize;
+/// Reads an ID3v2 tag appended to the file, held in `buf` with its header and footer.
+fn read_appended_id3v2(
+    buf: &[u8],
+    undecodable: &mut Vec<String>,
+) -> Result<Option<OptionalAudioTags>, Error> {
+    let mut x = 10;
+    if buf[5] & 0x40 != 0 {
+        // The size of the extended header includes itself.
+        x += match buf.get(10..14) {
+            Some(size) => size
+                .iter()
+                .fold(0, |size, c| (size << 7) | (*c as usize & 0x7f)),
+            None => return Ok(None),
+        };
+    }
+    let end = buf.len() - 10;
+    if x > end {
+        return Ok(None);
+    }
+    read_id3v2_frames(
+        buf,
+        x,
+        end - x,
+        buf[5] & 0x80 != 0,
+        buf[3],
+        buf[4],
+        undecodable,
+    )
+}
+
+/// Reports a tag skipped because of `error`, as an error if `fatal` and as a warning
+/// otherwise.
+fn report_tag_error(
+    state: &mut ParseState,
+    fatal: bool,
+    error: Error,
+    message: String,
+) -> Result<(), Error> {
+    if fatal {
+        state.recover::<()>(Err(error))?;
+    } else {
+        state.report(
+            Severity::Warning,
+            error.offset().unwrap_or(0),
+            error.kind(),
+            || message,
+        );
+    }
+    Ok(())
+}
+
+/// Reads the tag starting at `*i`, if any, and moves `*i` after it. `buf` holds the
+/// content from offset `base`.
+fn get_id3(
+    i: &mut u32,
+    buf: &[u8],
+    base: u32,
+    state: &mut ParseState,
+    options: &ParseOptions,
+) -> Result<(), Error> {
+    let mut x = (*i - base) as usize;
     // Get extended information
-    if buf.len() > 32 && x + 32 < buf.len() && &buf[x..x + 8] == b"APETAGEX" {
+    if let Some(header) = buf.get(x..).and_then(ApeHeader::read) {
         // APE
-        *i += 31; // skip APE header / footer
+        let items = x + APE_HEADER_SIZE;
+        if header.is_header() && items + header.size as usize <= buf.len() {
+            if !state.has_ape && options.ape && options.accepts_tag_size(header.total_size() as u64)
+            {
+                let tag =
+                    header.read_tag(&buf[items..items + header.size as usize - APE_HEADER_SIZE]);
+                state.has_ape = true;
+                state.push_tag(Event::Ape(tag));
+            }
+            *i = base + (items + header.size as usize) as u32;
+        } else {
+            // Either a footer, whose items have already been scanned, or a broken tag.
+            *i += APE_HEADER_SIZE as u32;
+            if header.is_header() {
+                report_tag_error(
+                    state,
+                    options.bad_tag_error,
+                    Error::new(ErrorKind::BadTagHeader)
+                        .with_offset((*i - APE_HEADER_SIZE as u32) as u64)
+                        .with_context("APE tag"),
+                    format!("APE tag of {} bytes past the end", header.total_size()),
+                )?;
+            }
+        }
         Ok(())
     } else if buf.len() > 127 && x + 127 < buf.len() && &buf[x..x + 3] == b"TAG" {
         // V1
-        if meta.tag.is_some() {
-            return Err(Error::DuplicatedIDV3);
+        if state.has_id3v1 {
+            // Duplicates don't matter when ID3v1 tags aren't read.
+            if options.id3v1 {
+                report_tag_error(
+                    state,
+                    options.duplicate_id3v1_error,
+                    Error::new(ErrorKind::DuplicatedIDV3)
+                        .with_offset(*i as u64)
+                        .with_context("ID3v1 tag"),
+                    "Second ID3v1 tag".to_owned(),
+                )?;
+            }
+            *i += 128;
+            return Ok(());
         }
-        if let Some(last) = meta.frames.last_mut() {
+        if let Some(last) = state.last.as_mut() {
             if *i <= last.size {
                 return Ok(());
             }
             last.size = *i - last.size - 1;
         }
         *i += 126;
-        // tag v1
-        meta.tag = Some(AudioTag {
-            title: create_utf8_str(&buf[x + 3..][..30]),
-            artist: create_utf8_str(&buf[x + 33..][..30]),
-            album: create_utf8_str(&buf[x + 63..][..30]),
-            year: create_utf8_str(&buf[x + 93..][..4])
-                .parse::<u16>()
-                .unwrap_or(0),
-            comment: create_utf8_str(&buf[x + 97..][..if buf[x + 97 + 28] != 0 { 30 } else { 28 }]),
-            genre: Genre::from(buf[x + 127]),
-        });
+        state.has_id3v1 = true;
+        if options.id3v1 {
+            state.push_tag(Event::Id3v1(read_id3v1(
+                &buf[x..x + 128],
+                None,
+                options.codepage,
+            )));
+        }
         Ok(())
     } else if buf.len() > x + 13 && &buf[x..x + 3] == b"ID3" {
         // V2 and above
+        let start = *i;
         let maj_version = buf[x + 3];
         let min_version = buf[x + 4];
 
+        // Where the tag ends according to its header, to skip it when it can't be read.
+        let tag_end = start
+            + 10
+            + buf[x + 6..x + 10]
+                .iter()
+                .fold(0, |size, c| (size << 7) | (*c as u32 & 0x7f));
+
         if maj_version > 4 {
-            return Ok(());
+            *i = tag_end;
+            return report_tag_error(
+                state,
+                options.bad_tag_error,
+                Error::new(ErrorKind::UnsupportedVersion)
+                    .with_offset(start as u64)
+                    .with_context("ID3v2 tag"),
+                format!("ID3v2.{} tag", maj_version),
+            );
         }
 
         let tag_size = ((buf[x + 9] as usize) & 0xFF)
@@ -62,7 +370,7 @@ fn get_id3(i: &mut u32, buf: &[u8], meta: &mut MP3Metadata) -> Result<(), Error>
 
         if has_extended_header {
             if x + 4 >= buf.len() {
-                *i = x as u32;
+                *i = base + x as u32;
                 return Ok(());
             }
             let header_size = ((buf[x] as u32) << 21)
@@ -70,325 +378,551 @@ fn get_id3(i: &mut u32, buf: &[u8], meta: &mut MP3Metadata) -> Result<(), Error>
                 | ((buf[x + 2] as u32) << 7)
                 | buf[x + 3] as u32;
             if header_size < 4 {
-                return Ok(());
+                *i = tag_end;
+                return report_tag_error(
+                    state,
+                    options.bad_tag_error,
+                    Error::new(ErrorKind::BadTagHeader)
+                        .with_offset(start as u64)
+                        .with_context("ID3v2 tag"),
+                    format!("Extended header of {} bytes", header_size),
+                );
             }
             x += header_size as usize - 4;
         }
 
-        *i = x as u32 + tag_size as u32;
+        *i = base + x as u32 + tag_size as u32;
+        if !(options.id3v2 && options.accepts_tag_size(tag_size as u64)) {
+            return Ok(());
+        }
         if x + tag_size >= buf.len() {
             return Ok(());
         }
 
-        // Recreate the tag if desynchronization is used inside; we need to replace
-        // 0xFF 0x00 with 0xFF
-        let mut v = Vec::new();
-        let (buf, length) = if use_sync {
-            let mut new_pos = 0;
-            let mut skip = false;
-            v.reserve(tag_size);
+        let mut undecodable = Vec::new();
+        let op = read_id3v2_frames(
+            buf,
+            x,
+            tag_size,
+            use_sync,
+            maj_version,
+            min_version,
+            &mut undecodable,
+        )
+        .map_err(|e| e.with_offset(start as u64).with_context("ID3v2 tag"));
+        let op = state.recover(op)?.flatten();
+        state.report_undecodable(start as u64, undecodable);
+        if let Some(mut op) = op {
+            op.position = state.frames as u32;
+            state.push_tag(Event::Id3v2(Box::new(op)));
+        }
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
 
-            for i in 0..tag_size {
-                if skip {
-                    skip = false;
-                    continue;
-                }
-                if i + 1 >= buf.len() {
-                    return Ok(());
-                }
-                if i + 1 < tag_size && buf[i] == 0xFF && buf[i + 1] == 0 {
-                    if let Some(elem) = v.get_mut(new_pos) {
-                        *elem = 0xFF;
-                    } else {
-                        return Err(Error::InvalidData);
-                    }
-                    new_pos += 1;
-                    skip = true;
-                    continue;
-                }
-                if new_pos >= v.len() {
-                    return Ok(());
+/// Reads the frames of an ID3v2 tag, starting at `x` in `buf` and spanning `tag_size`
+/// bytes. Returns `None` if none of them is supported.
+fn read_id3v2_frames(
+    buf: &[u8],
+    x: usize,
+    tag_size: usize,
+    use_sync: bool,
+    maj_version: u8,
+    min_version: u8,
+    undecodable: &mut Vec<String>,
+) -> Result<Option<OptionalAudioTags>, Error> {
+    // Recreate the tag if desynchronization is used inside; we need to replace
+    // 0xFF 0x00 with 0xFF
+    let mut v = Vec::new();
+    let (buf, length) = if use_sync {
+        let mut new_pos = 0;
+        let mut skip = false;
+        v.reserve(tag_size);
+
+        for i in 0..tag_size {
+            if skip {
+                skip = false;
+                continue;
+            }
+            if i + 1 >= buf.len() {
+                return Ok(None);
+            }
+            if i + 1 < tag_size && buf[i] == 0xFF && buf[i + 1] == 0 {
+                if let Some(elem) = v.get_mut(new_pos) {
+                    *elem = 0xFF;
+                } else {
+                    return Err(ErrorKind::InvalidData.into());
                 }
-                v[new_pos] = buf[i];
                 new_pos += 1;
+                skip = true;
+                continue;
+            }
+            if new_pos >= v.len() {
+                return Ok(None);
             }
-            (v.as_slice(), new_pos)
+            v[new_pos] = buf[i];
+            new_pos += 1;
+        }
+        (v.as_slice(), new_pos)
+    } else {
+        (buf, tag_size)
+    };
+
+    let mut pos = x;
+    let id3_frame_size = if maj_version < 3 { 6 } else { 10 };
+    let mut op = OptionalAudioTags::default();
+    let mut changes = false;
+    loop {
+        if pos + id3_frame_size > x + length {
+            break;
+        }
+
+        // Check if there is there a frame.
+        let c = buf[pos];
+        #[allow(clippy::manual_range_contains)]
+        if c < b'A' || c > b'Z' {
+            break;
+        }
+
+        // Frame name is 3 chars in pre-ID3v3 and 4 chars after
+        let (frame_name, frame_size) = if maj_version < 3 {
+            (
+                &buf[pos..pos + 3],
+                (buf[pos + 5] as u32 & 0xFF)
+                    | ((buf[pos + 4] as u32 & 0xFF) << 8)
+                    | ((buf[pos + 3] as u32 & 0xFF) << 16),
+            )
+        } else if maj_version < 4 {
+            (
+                &buf[pos..pos + 4],
+                (buf[pos + 7] as u32 & 0xFF)
+                    | ((buf[pos + 6] as u32 & 0xFF) << 8)
+                    | ((buf[pos + 5] as u32 & 0xFF) << 16)
+                    | ((buf[pos + 4] as u32 & 0xFF) << 24),
+            )
         } else {
-            (buf, tag_size)
+            (
+                &buf[pos..pos + 4],
+                (buf[pos + 7] as u32 & 0xFF)
+                    | ((buf[pos + 6] as u32 & 0xFF) << 7)
+                    | ((buf[pos + 5] as u32 & 0xFF) << 14)
+                    | ((buf[pos + 4] as u32 & 0xFF) << 21),
+            )
         };
 
-        let mut pos = x;
-        let id3_frame_size = if maj_version < 3 { 6 } else { 10 };
-        let mut op = OptionalAudioTags::default();
-        let mut changes = false;
-        loop {
-            if pos + id3_frame_size > x + length {
-                break;
-            }
+        pos += id3_frame_size;
+        if pos + frame_size as usize > x + length {
+            break;
+        }
 
-            // Check if there is there a frame.
-            let c = buf[pos];
-            #[allow(clippy::manual_range_contains)]
-            if c < b'A' || c > b'Z' {
-                break;
+        // Whether the text of the frame couldn't be decoded without losing characters.
+        let mut lossy = false;
+        // http://id3.org/id3v2.3.0#Declared_ID3v2_frames
+        match frame_name {
+            // -----------------------
+            // ----- TEXT FRAMES -----
+            // -----------------------
+            b"TALB" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.album_movie_show)
             }
-
-            // Frame name is 3 chars in pre-ID3v3 and 4 chars after
-            let (frame_name, frame_size) = if maj_version < 3 {
-                (
-                    &buf[pos..pos + 3],
-                    (buf[pos + 5] as u32 & 0xFF)
-                        | ((buf[pos + 4] as u32 & 0xFF) << 8)
-                        | ((buf[pos + 3] as u32 & 0xFF) << 16),
-                )
-            } else if maj_version < 4 {
-                (
-                    &buf[pos..pos + 4],
-                    (buf[pos + 7] as u32 & 0xFF)
-                        | ((buf[pos + 6] as u32 & 0xFF) << 8)
-                        | ((buf[pos + 5] as u32 & 0xFF) << 16)
-                        | ((buf[pos + 4] as u32 & 0xFF) << 24),
-                )
-            } else {
-                (
-                    &buf[pos..pos + 4],
-                    (buf[pos + 7] as u32 & 0xFF)
-                        | ((buf[pos + 6] as u32 & 0xFF) << 7)
-                        | ((buf[pos + 5] as u32 & 0xFF) << 14)
-                        | ((buf[pos + 4] as u32 & 0xFF) << 21),
-                )
-            };
-
-            pos += id3_frame_size;
-            if pos + frame_size as usize > x + length {
-                break;
+            b"TBPM" => lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.bpm),
+            b"TCOM" => {
+                lossy = get_text_fields(buf, pos, frame_size, &mut changes, &mut op.composers)
             }
-
-            // http://id3.org/id3v2.3.0#Declared_ID3v2_frames
-            match frame_name {
-                // -----------------------
-                // ----- TEXT FRAMES -----
-                // -----------------------
-                b"TALB" => {
-                    get_text_field(buf, pos, frame_size, &mut changes, &mut op.album_movie_show)
-                }
-                b"TBPM" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.bpm),
-                b"TCOM" => get_text_fields(buf, pos, frame_size, &mut changes, &mut op.composers),
-                b"TCON" => {
-                    let mut s = None;
-                    get_text_field(buf, pos, frame_size, &mut changes, &mut s);
-                    if let Some(s) = s {
-                        if !s.is_empty() {
-                            if s.starts_with('(') && s.ends_with(')') {
-                                let v = s
-                                    .split(')')
-                                    .collect::<Vec<&str>>()
-                                    .into_iter()
-                                    .filter_map(|a| match a.replace('(', "").parse::<u8>() {
-                                        Ok(num) => Some(Genre::from(num)),
-                                        _ => None,
-                                    })
-                                    .collect::<Vec<Genre>>();
-                                if !v.is_empty() {
-                                    for entry in v {
-                                        op.content_type.push(entry);
-                                    }
-                                } else {
-                                    op.content_type.push(Genre::from(s.as_str()));
+            b"TCON" => {
+                let mut s = None;
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut s);
+                if let Some(s) = s {
+                    if !s.is_empty() {
+                        if s.starts_with('(') && s.ends_with(')') {
+                            let v = s
+                                .split(')')
+                                .collect::<Vec<&str>>()
+                                .into_iter()
+                                .filter_map(|a| match a.replace('(', "").parse::<u8>() {
+                                    Ok(num) => Some(Genre::from(num)),
+                                    _ => None,
+                                })
+                                .collect::<Vec<Genre>>();
+                            if !v.is_empty() {
+                                for entry in v {
+                                    op.content_type.push(entry);
                                 }
                             } else {
                                 op.content_type.push(Genre::from(s.as_str()));
                             }
+                        } else {
+                            op.content_type.push(Genre::from(s.as_str()));
                         }
                     }
                 }
-                b"TCOP" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.copyright),
-                b"TDAT" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.date),
-                b"TDLY" => {
-                    get_text_field(buf, pos, frame_size, &mut changes, &mut op.playlist_delay)
-                }
-                b"TENC" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.encoded_by),
-                b"TEXT" => {
-                    get_text_fields(buf, pos, frame_size, &mut changes, &mut op.text_writers)
-                }
-                b"TFLT" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.file_type),
-                b"TIME" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.time),
-                b"TIT" | b"TIT2" => {
-                    get_text_field(buf, pos, frame_size, &mut changes, &mut op.title)
-                }
-                b"TIT1" => get_text_field(
+            }
+            b"TCOP" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.copyright)
+            }
+            b"TDAT" => lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.date),
+            b"TDLY" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.playlist_delay)
+            }
+            b"TENC" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.encoded_by)
+            }
+            b"TEXT" => {
+                lossy = get_text_fields(buf, pos, frame_size, &mut changes, &mut op.text_writers)
+            }
+            b"TFLT" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.file_type)
+            }
+            b"TIME" => lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.time),
+            b"TIT" | b"TIT2" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.title)
+            }
+            b"TIT1" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.content_group_description,
-                ),
-                b"TIT3" => get_text_field(
+                )
+            }
+            b"TIT3" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.subtitle_refinement_description,
-                ),
-                b"TKEY" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.initial_key),
-                b"TLAN" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.language),
-                b"TLEN" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.length),
-                b"TMED" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.media_type),
-                b"TOAL" => get_text_field(
+                )
+            }
+            b"TKEY" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.initial_key)
+            }
+            b"TLAN" => lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.language),
+            b"TLEN" => lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.length),
+            b"TMED" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.media_type)
+            }
+            b"TOAL" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.original_album_move_show_title,
-                ),
-                b"TOFN" => get_text_field(
+                )
+            }
+            b"TOFN" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.original_filename,
-                ),
-                b"TOLY" => get_text_fields(
+                )
+            }
+            b"TOLY" => {
+                lossy = get_text_fields(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.original_text_writers,
-                ),
-                b"TOPE" => {
+                )
+            }
+            b"TOPE" => {
+                lossy =
                     get_text_fields(buf, pos, frame_size, &mut changes, &mut op.original_artists)
-                }
-                b"TORY" => get_text_field(
+            }
+            b"TORY" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.original_release_year,
-                ),
-                b"TOWN" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.file_owner),
-                b"TPE1" => get_text_fields(buf, pos, frame_size, &mut changes, &mut op.performers),
-                b"TPE2" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.band),
-                b"TPE3" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.conductor),
-                b"TPE4" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.interpreted),
-                b"TPOS" => {
-                    get_text_field(buf, pos, frame_size, &mut changes, &mut op.part_of_a_set)
-                }
-                b"TPUB" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.publisher),
-                b"TRCK" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.track_number),
-                b"TRDA" => {
-                    get_text_field(buf, pos, frame_size, &mut changes, &mut op.recording_dates)
-                }
-                b"TRSN" => get_text_field(
+                )
+            }
+            b"TOWN" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.file_owner)
+            }
+            b"TPE1" => {
+                lossy = get_text_fields(buf, pos, frame_size, &mut changes, &mut op.performers)
+            }
+            b"TPE2" => lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.band),
+            b"TPE3" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.conductor)
+            }
+            b"TPE4" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.interpreted)
+            }
+            b"TPOS" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.part_of_a_set)
+            }
+            b"TPUB" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.publisher)
+            }
+            b"TRCK" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.track_number)
+            }
+            b"TRDA" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.recording_dates)
+            }
+            b"TRSN" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.internet_radio_station_name,
-                ),
-                b"TRSO" => get_text_field(
+                )
+            }
+            b"TRSO" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.internet_radio_station_owner,
-                ),
-                b"TSIZ" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.size),
-                b"TSRC" => get_text_field(
+                )
+            }
+            b"TSIZ" => lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.size),
+            b"TSRC" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.international_standard_recording_code,
-                ),
-                b"TSSE" => get_text_field(
+                )
+            }
+            b"TSSE" => {
+                lossy = get_text_field(
                     buf,
                     pos,
                     frame_size,
                     &mut changes,
                     &mut op.soft_hard_setting,
-                ),
-                b"TYER" => get_text_field(buf, pos, frame_size, &mut changes, &mut op.year),
-                b"IPLS" => {
-                    get_text_field(buf, pos, frame_size, &mut changes, &mut op.involved_people)
-                }
-                // ----------------------
-                // ----- URL FRAMES -----
-                // ----------------------
-                b"WCOM" => get_url_fields(
-                    buf,
-                    pos,
-                    frame_size,
-                    &mut changes,
-                    &mut op.commercial_info_url,
-                ),
-                b"WCOP" => get_url_field(
-                    buf,
-                    pos,
-                    frame_size,
-                    &mut changes,
-                    &mut op.copyright_info_url,
-                ),
-                b"WOAF" => {
-                    get_url_field(buf, pos, frame_size, &mut changes, &mut op.official_webpage)
-                }
-                b"WOAR" => get_url_fields(
-                    buf,
-                    pos,
-                    frame_size,
-                    &mut changes,
-                    &mut op.official_artist_webpage,
-                ),
-                b"WOAS" => get_url_field(
-                    buf,
-                    pos,
-                    frame_size,
-                    &mut changes,
-                    &mut op.official_audio_source_webpage,
-                ),
-                b"WORS" => get_url_field(
-                    buf,
-                    pos,
-                    frame_size,
-                    &mut changes,
-                    &mut op.official_internet_radio_webpage,
-                ),
-                b"WPAY" => get_url_field(buf, pos, frame_size, &mut changes, &mut op.payment_url),
-                b"WPUB" => get_url_field(
-                    buf,
-                    pos,
-                    frame_size,
-                    &mut changes,
-                    &mut op.publishers_official_webpage,
-                ),
-                _ => {
-                    // TODO: handle other type of fields, like picture
+                )
+            }
+            b"TYER" => lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.year),
+            b"IPLS" => {
+                lossy = get_text_field(buf, pos, frame_size, &mut changes, &mut op.involved_people)
+            }
+            b"MLL" | b"MLLT" if op.mpeg_location_lookup_table.is_none() => {
+                op.mpeg_location_lookup_table = get_mllt(buf, pos, frame_size);
+                changes = true;
+            }
+            b"COM" | b"COMM" => {
+                if let Some(comment) = get_comment(buf, pos, frame_size) {
+                    op.comments.push(comment);
+                    changes = true;
                 }
-            };
-
-            pos += frame_size as usize;
-        }
-        if changes {
-            op.position = meta.frames.len() as u32;
-            op.minor_version = min_version;
-            op.major_version = maj_version;
-            meta.optional_info.push(op);
+            }
+            // ----------------------
+            // ----- URL FRAMES -----
+            // ----------------------
+            b"WCOM" => get_url_fields(
+                buf,
+                pos,
+                frame_size,
+                &mut changes,
+                &mut op.commercial_info_url,
+            ),
+            b"WCOP" => get_url_field(
+                buf,
+                pos,
+                frame_size,
+                &mut changes,
+                &mut op.copyright_info_url,
+            ),
+            b"WOAF" => get_url_field(buf, pos, frame_size, &mut changes, &mut op.official_webpage),
+            b"WOAR" => get_url_fields(
+                buf,
+                pos,
+                frame_size,
+                &mut changes,
+                &mut op.official_artist_webpage,
+            ),
+            b"WOAS" => get_url_field(
+                buf,
+                pos,
+                frame_size,
+                &mut changes,
+                &mut op.official_audio_source_webpage,
+            ),
+            b"WORS" => get_url_field(
+                buf,
+                pos,
+                frame_size,
+                &mut changes,
+                &mut op.official_internet_radio_webpage,
+            ),
+            b"WPAY" => get_url_field(buf, pos, frame_size, &mut changes, &mut op.payment_url),
+            b"WPUB" => get_url_field(
+                buf,
+                pos,
+                frame_size,
+                &mut changes,
+                &mut op.publishers_official_webpage,
+            ),
+            _ => {
+                // TODO: handle other type of fields, like picture
+            }
+        };
+        if lossy {
+            undecodable.push(String::from_utf8_lossy(frame_name).into_owned());
         }
-        Ok(())
+
+        pos += frame_size as usize;
+    }
+    if changes {
+        op.minor_version = min_version;
+        op.major_version = maj_version;
+        Ok(Some(op))
     } else {
-        Ok(())
+        Ok(None)
     }
 }
 
-fn read_header(buf: &[u8], i: &mut u32, meta: &mut MP3Metadata) -> Result<bool, Error> {
-    let header = ((buf[*i as usize] as u32) << 24)
-        | ((buf[*i as usize + 1] as u32) << 16)
-        | ((buf[*i as usize + 2] as u32) << 8)
-        | (buf[*i as usize + 3] as u32);
+/// Free-format frames can't be longer than this (Layer I at 448 kb/s and 32 kHz is
+/// already above the largest standard frame).
+const MAX_FREE_FORMAT_SIZE: usize = 8192;
+
+/// Length in bytes, padding excluded, of the free-format frame starting at `pos` in
+/// `buf`, which holds the content from offset `base`. The
+/// bitrate is constant in a free-format stream, so the length of the previous frame is
+/// reused when there is one, otherwise the next frame header with the same version,
+/// layer and sampling frequency is looked for.
+fn get_free_format_size(
+    buf: &[u8],
+    pos: usize,
+    base: u32,
+    header: u32,
+    state: &ParseState,
+) -> Option<u32> {
+    let version = Version::from((header >> 19) & 3);
+    let layer = Layer::from((header >> 17) & 3);
+    let slot_size = get_slot_size(layer);
+    if let Some(last) = &state.last {
+        if last.offset + last.size == base + pos as u32
+            && last.version == version
+            && last.layer == layer
+        {
+            return Some(last.size - last.padding as u32 * slot_size);
+        }
+    }
+    // Sync word, version, layer, bitrate index and sampling frequency.
+    let mask = 0xfffe_fc00;
+    let padding = (header >> 9) & 1;
+    // Free-format frames aren't expected to be shorter than frames at the lowest
+    // standard bitrate.
+    let sampling_freq = SAMPLING_FREQ[get_samp_line(version)][((header >> 10) & 0x3) as usize];
+    let min_bitrate = BITRATES[get_line(version, layer)][1] as u32 * 1000;
+    let min_size = get_frame_size(version, layer, min_bitrate, sampling_freq, false) as usize;
+    let end = buf.len().min(pos + MAX_FREE_FORMAT_SIZE);
+    (pos + min_size.max(4)..end.saturating_sub(3))
+        .find(|next| {
+            let next_header =
+                u32::from_be_bytes([buf[*next], buf[*next + 1], buf[*next + 2], buf[*next + 3]]);
+            next_header & mask == header & mask
+        })
+        .map(|next| (next - pos) as u32 - padding * slot_size)
+}
+
+/// Returns the frame header starting at `pos`, if there is a valid one.
+fn get_header(buf: &[u8], pos: usize) -> Option<u32> {
+    let bytes = buf.get(pos..pos + 4)?;
+    let header = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
     if header & 0xffe00000 == 0xffe00000
         && header & (3 << 17) != 0
         && header & (0xf << 12) != (0xf << 12)
         && header & (3 << 10) != (3 << 10)
     {
+        Some(header)
+    } else {
+        None
+    }
+}
+
+/// Length in bytes of the frame with the given header, starting at `pos` in `buf`, which
+/// holds the content from offset `base`.
+fn get_header_frame_size(
+    buf: &[u8],
+    pos: usize,
+    base: u32,
+    header: u32,
+    state: &ParseState,
+) -> Option<u32> {
+    let version = Version::from((header >> 19) & 3);
+    let layer = Layer::from((header >> 17) & 3);
+    let padding = (header >> 9) & 1 == 1;
+    let bitrate_index = ((header >> 12) & 0xF) as usize;
+    let size = if bitrate_index == 0 {
+        get_free_format_size(buf, pos, base, header, state)? + padding as u32 * get_slot_size(layer)
+    } else {
+        get_frame_size(
+            version,
+            layer,
+            BITRATES[get_line(version, layer)][bitrate_index] as u32 * 1000,
+            SAMPLING_FREQ[get_samp_line(version)][((header >> 10) & 0x3) as usize],
+            padding,
+        )
+    };
+    if size < 4 {
+        None
+    } else {
+        Some(size)
+    }
+}
+
+/// Checks that the `count` frames following the one starting at `pos` have headers
+/// consistent with it. Reaching the end of the data or a tag counts as a confirmation.
+fn confirm_sync(
+    buf: &[u8],
+    pos: usize,
+    base: u32,
+    header: u32,
+    size: u32,
+    count: u32,
+    state: &ParseState,
+) -> bool {
+    // Sync word, version, layer and sampling frequency.
+    let mask = 0xfffe_0c00;
+    let mut next = pos + size as usize;
+    for _ in 0..count {
+        if next + 4 > buf.len() {
+            return true;
+        }
+        let tail = &buf[next..];
+        if tail.starts_with(b"ID3") || tail.starts_with(b"TAG") || tail.starts_with(b"APETAGEX") {
+            return true;
+        }
+        let next_header = match get_header(buf, next) {
+            Some(next_header) if next_header & mask == header & mask => next_header,
+            _ => return false,
+        };
+        match get_header_frame_size(buf, next, base, next_header, state) {
+            Some(size) => next += size as usize,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Reads the frame starting at `*i`, if any, and moves `*i` after it. `buf` holds the
+/// content from offset `base`.
+fn read_header(
+    buf: &[u8],
+    base: u32,
+    i: &mut u32,
+    state: &mut ParseState,
+    options: &ParseOptions,
+) -> Result<bool, Error> {
+    let pos = (*i - base) as usize;
+    if let Some(header) = get_header(buf, pos) {
         let mut frame: Frame = Default::default();
 
         frame.version = Version::from((header >> 19) & 3);
@@ -403,65 +937,82 @@ fn read_header(buf: &[u8], i: &mut u32, meta: &mut MP3Metadata) -> Result<bool,
         frame.private_bit = (header >> 8) & 1 == 1;
 
         frame.chan_type = ChannelType::from((header >> 6) & 3);
-        let (intensity, ms_stereo) = match (header >> 4) & 3 {
-            0x1 => (true, false),
-            0x2 => (false, true),
-            0x3 => (true, true),
-            /*0x00*/ _ => (false, false),
-        };
-        frame.intensity_stereo = intensity;
-        frame.ms_stereo = ms_stereo;
+        if frame.chan_type == ChannelType::JointStereo {
+            frame.mode_extension = ModeExtension::read(frame.layer, (header >> 4) & 3);
+        }
         frame.copyright = Copyright::from((header >> 3) & 1);
         frame.status = Status::from((header >> 2) & 1);
         frame.emphasis = Emphasis::from(header & 0x03);
         frame.duration = compute_duration(frame.version, frame.layer, frame.sampling_freq);
-        frame.position = meta.duration;
+        frame.position = state.duration;
         frame.offset = *i;
 
-        if let Some(dur) = frame.duration {
-            meta.duration += dur;
-        }
-        /*frame.size = if frame.layer == Layer::Layer1 && frame.sampling_freq > 0 {
-            /*println!("{:4}: (12000 * {} / {} + {}) * 4 = {}", i, frame.bitrate as u64, frame.sampling_freq as u64,
-                if frame.slot { 1 } else { 0 },
-                    (12000 * frame.bitrate as u64 / frame.sampling_freq as u64 +
-                if frame.slot { 1 } else { 0 }) * 4);*/
-
-            (12000 * frame.bitrate as u64 / frame.sampling_freq as u64 +
-                if frame.slot { 1 } else { 0 }) * 4
-        } else if (frame.layer == Layer::Layer2 || frame.layer == Layer::Layer3) && frame.sampling_freq > 0 {
-            /*println!("{:4}: 144000 * {} / {} + {} = {}", i, frame.bitrate as u64, frame.sampling_freq as u64,
-                if frame.slot { 1 } else { 0 },
-                    144000 * frame.bitrate as u64 / frame.sampling_freq as u64 +
-                if frame.slot { 1 } else { 0 });*/
-
-            144000 * frame.bitrate as u64 / frame.sampling_freq as u64 +
-                if frame.slot { 1 } else { 0 }
-        } else {
-            continue 'a;
-        } as u32;*/
-        let samples_per_frame = match frame.layer {
-            Layer::Layer3 => {
-                if frame.version == Version::MPEG1 {
-                    1152
-                } else {
-                    576
-                }
-            }
-            Layer::Layer2 => 1152,
-            Layer::Layer1 => 384,
-            _ => unreachable!(),
+        frame.size = match get_header_frame_size(buf, pos, base, header, state) {
+            Some(size) => size,
+            None => return Ok(false),
         };
-        frame.size = (samples_per_frame as u64 / 8 * frame.bitrate as u64 * 1000
-            / frame.sampling_freq as u64) as u32;
-        if frame.size < 1 {
+        if (header >> 12) & 0xF == 0 {
+            // Free format.
+            let size = frame.size - frame.padding as u32 * get_slot_size(frame.layer);
+            frame.bitrate =
+                (get_bitrate(frame.version, frame.layer, size, frame.sampling_freq) / 1000) as u16;
+        }
+        // Only look ahead when (re)synchronizing, not for frames following each other.
+        let synchronized = state
+            .last
+            .as_ref()
+            .map(|last| last.offset + last.size == *i)
+            .unwrap_or(false);
+        if !synchronized
+            && !confirm_sync(
+                buf,
+                pos,
+                base,
+                header,
+                frame.size,
+                options.sync_frames,
+                state,
+            )
+        {
+            state.resyncs.push(*i);
             return Ok(false);
         }
-        if frame.padding {
-            frame.size += 1;
+        if let Some(dur) = frame.duration {
+            state.duration += dur;
+        }
+        frame.first_sample = state.total_samples;
+        state.total_samples += get_samples_per_frame(frame.version, frame.layer) as u64;
+        let end = buf.len().min(pos + frame.size as usize);
+        let frame_buf = &buf[pos..end];
+        if frame.crc == CRC::Added {
+            frame.crc_checksum = frame_buf
+                .get(4..6)
+                .map(|checksum| u16::from_be_bytes([checksum[0], checksum[1]]));
+            frame.crc_valid = check_frame_crc(frame_buf, &frame);
+            if frame.crc_valid == Some(false) {
+                state.crc_failures += 1;
+            }
+        }
+        if options.side_info {
+            frame.side_info = read_side_info(frame_buf, &frame);
+        }
+        if state.frames == 0 && state.vbr_header.is_none() {
+            state.vbr_header = read_vbr_header(frame_buf, &frame);
+        }
+        if state.vbr_header.as_ref().map(|header| header.offset) != Some(frame.offset) {
+            state
+                .summary
+                .add_frame(&frame, ((header >> 12) & 0xF) as usize);
+        }
+        let pushed = match state.segments.last_mut() {
+            Some(segment) => segment.push(&frame),
+            None => false,
+        };
+        if !pushed {
+            state.segments.push(Segment::new(&frame, state.frames));
         }
         *i += frame.size;
-        meta.frames.push(frame);
+        state.push_frame(frame);
         Ok(true)
     } else {
         Ok(false)
@@ -472,59 +1023,639 @@ pub fn read_from_file<P>(file: P) -> Result<MP3Metadata, Error>
 where
     P: AsRef<Path>,
 {
-    if let Ok(mut fd) = File::open(file) {
-        let mut buf = Vec::new();
+    read_from_file_with(file, &ParseOptions::default())
+}
 
-        match fd.read_to_end(&mut buf) {
-            Ok(_) => read_from_slice(&buf),
-            Err(_) => Err(Error::FileError),
-        }
-    } else {
-        Err(Error::FileError)
+pub fn read_from_file_with<P>(file: P, options: &ParseOptions) -> Result<MP3Metadata, Error>
+where
+    P: AsRef<Path>,
+{
+    match File::open(file) {
+        Ok(fd) => read_from_reader_with(fd, options),
+        Err(e) => Err(Error::new(ErrorKind::FileError).with_source(e)),
     }
 }
 
+/// Reads the metadata from `reader` without loading all of its content in memory: only
+/// the tags and a few frames at a time are.
+pub fn read_from_reader<R>(reader: R) -> Result<MP3Metadata, Error>
+where
+    R: Read + Seek,
+{
+    read_from_reader_with(reader, &ParseOptions::default())
+}
+
+pub fn read_from_reader_with<R>(reader: R, options: &ParseOptions) -> Result<MP3Metadata, Error>
+where
+    R: Read + Seek,
+{
+    read_from_source(ReaderSource::new(reader)?, options, false).map(|(meta, _)| meta)
+}
+
 pub fn read_from_slice(buf: &[u8]) -> Result<MP3Metadata, Error> {
-    let mut meta = MP3Metadata {
-        frames: Vec::new(),
-        duration: Duration::new(0, 0),
-        tag: None,
-        optional_info: Vec::new(),
+    read_from_slice_with(buf, &ParseOptions::default())
+}
+
+pub fn read_from_slice_with(buf: &[u8], options: &ParseOptions) -> Result<MP3Metadata, Error> {
+    read_from_source(SliceSource::new(buf), options, false).map(|(meta, _)| meta)
+}
+
+/// Reads the metadata like [`read_from_file`], but goes on when problems are found,
+/// returning them alongside the metadata read. Only failing to read the file or finding
+/// no frame are errors.
+pub fn read_from_file_lenient<P>(file: P) -> Result<(MP3Metadata, Vec<Diagnostic>), Error>
+where
+    P: AsRef<Path>,
+{
+    read_from_file_lenient_with(file, &ParseOptions::default())
+}
+
+pub fn read_from_file_lenient_with<P>(
+    file: P,
+    options: &ParseOptions,
+) -> Result<(MP3Metadata, Vec<Diagnostic>), Error>
+where
+    P: AsRef<Path>,
+{
+    match File::open(file) {
+        Ok(fd) => read_from_reader_lenient_with(fd, options),
+        Err(e) => Err(Error::new(ErrorKind::FileError).with_source(e)),
+    }
+}
+
+pub fn read_from_reader_lenient<R>(reader: R) -> Result<(MP3Metadata, Vec<Diagnostic>), Error>
+where
+    R: Read + Seek,
+{
+    read_from_reader_lenient_with(reader, &ParseOptions::default())
+}
+
+pub fn read_from_reader_lenient_with<R>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<(MP3Metadata, Vec<Diagnostic>), Error>
+where
+    R: Read + Seek,
+{
+    read_from_source(ReaderSource::new(reader)?, options, true)
+}
+
+pub fn read_from_slice_lenient(buf: &[u8]) -> Result<(MP3Metadata, Vec<Diagnostic>), Error> {
+    read_from_slice_lenient_with(buf, &ParseOptions::default())
+}
+
+pub fn read_from_slice_lenient_with(
+    buf: &[u8],
+    options: &ParseOptions,
+) -> Result<(MP3Metadata, Vec<Diagnostic>), Error> {
+    read_from_source(SliceSource::new(buf), options, true)
+}
+
+/// Reads the tags of a file without going through its frames: only the ID3v2 tags at
+/// its start and the tags at its end are read.
+pub fn read_tags_from_file<P>(file: P) -> Result<MP3Tags, Error>
+where
+    P: AsRef<Path>,
+{
+    read_tags_from_file_with(file, &ParseOptions::default())
+}
+
+pub fn read_tags_from_file_with<P>(file: P, options: &ParseOptions) -> Result<MP3Tags, Error>
+where
+    P: AsRef<Path>,
+{
+    match File::open(file) {
+        Ok(fd) => read_tags_from_reader_with(fd, options),
+        Err(e) => Err(Error::new(ErrorKind::FileError).with_source(e)),
+    }
+}
+
+pub fn read_tags_from_reader<R>(reader: R) -> Result<MP3Tags, Error>
+where
+    R: Read + Seek,
+{
+    read_tags_from_reader_with(reader, &ParseOptions::default())
+}
+
+pub fn read_tags_from_reader_with<R>(reader: R, options: &ParseOptions) -> Result<MP3Tags, Error>
+where
+    R: Read + Seek,
+{
+    read_tags_from_source(
+        ReaderSource::with_chunk_size(reader, TAGS_CHUNK_SIZE)?,
+        options,
+    )
+}
+
+pub fn read_tags_from_slice(buf: &[u8]) -> Result<MP3Tags, Error> {
+    read_tags_from_slice_with(buf, &ParseOptions::default())
+}
+
+pub fn read_tags_from_slice_with(buf: &[u8], options: &ParseOptions) -> Result<MP3Tags, Error> {
+    read_tags_from_source(SliceSource::new(buf), options)
+}
+
+/// Bytes read at once when only looking for tags: enough for most tags without cover
+/// pictures.
+const TAGS_CHUNK_SIZE: usize = 4096;
+
+/// Estimates the duration of a file from its first frames: from the number of frames
+/// stored in its Xing or VBRI header if any, otherwise from the bitrate of the first
+/// frame and the size of the audio data. The estimate matches
+/// [`MP3Metadata::duration`] for CBR streams.
+pub fn estimate_duration_from_file<P>(file: P) -> Result<DurationEstimate, Error>
+where
+    P: AsRef<Path>,
+{
+    estimate_duration_from_file_with(file, &ParseOptions::default())
+}
+
+/// Same as [`estimate_duration_from_file`], with the tag and synchronization settings of
+/// `options`. The frames are always kept and a duplicate ID3v1 tag is never an error,
+/// since the estimate doesn't depend on them.
+pub fn estimate_duration_from_file_with<P>(
+    file: P,
+    options: &ParseOptions,
+) -> Result<DurationEstimate, Error>
+where
+    P: AsRef<Path>,
+{
+    match File::open(file) {
+        Ok(fd) => estimate_duration_from_reader_with(fd, options),
+        Err(e) => Err(Error::new(ErrorKind::FileError).with_source(e)),
+    }
+}
+
+pub fn estimate_duration_from_reader<R>(reader: R) -> Result<DurationEstimate, Error>
+where
+    R: Read + Seek,
+{
+    estimate_duration_from_reader_with(reader, &ParseOptions::default())
+}
+
+pub fn estimate_duration_from_reader_with<R>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<DurationEstimate, Error>
+where
+    R: Read + Seek,
+{
+    estimate_duration_from_source(
+        ReaderSource::with_chunk_size(reader, ESTIMATE_CHUNK_SIZE)?,
+        options,
+    )
+}
+
+pub fn estimate_duration_from_slice(buf: &[u8]) -> Result<DurationEstimate, Error> {
+    estimate_duration_from_slice_with(buf, &ParseOptions::default())
+}
+
+pub fn estimate_duration_from_slice_with(
+    buf: &[u8],
+    options: &ParseOptions,
+) -> Result<DurationEstimate, Error> {
+    estimate_duration_from_source(SliceSource::new(buf), options)
+}
+
+/// Number of frames looked at to estimate the duration.
+const ESTIMATE_FRAMES: usize = 8;
+
+/// Bytes read at once when estimating the duration: enough for the lookahead needed to
+/// confirm a frame header and a few frames.
+const ESTIMATE_CHUNK_SIZE: usize = 64 * 1024;
+
+fn estimate_duration_from_source<S: Source>(
+    source: S,
+    options: &ParseOptions,
+) -> Result<DurationEstimate, Error> {
+    let options = ParseOptions {
+        frames: true,
+        max_frames: None,
+        duplicate_id3v1_error: false,
+        ..options.clone()
+    };
+    let mut parser = Parser::new(source, &options);
+    let mut frames = Vec::with_capacity(ESTIMATE_FRAMES);
+    while frames.len() < ESTIMATE_FRAMES {
+        match parser.next() {
+            Some(event) => {
+                if let Event::Frame(frame) = event? {
+                    frames.push(frame);
+                }
+            }
+            None => break,
+        }
+    }
+    let first = frames
+        .first()
+        .ok_or_else(|| Error::new(ErrorKind::NotMP3))?;
+    let frame_duration =
+        compute_duration(first.version, first.layer, first.sampling_freq).unwrap_or_default();
+    if frames.len() < ESTIMATE_FRAMES {
+        // All the frames have been found.
+        return Ok(DurationEstimate {
+            duration: frame_duration * frames.len() as u32,
+            confidence: Confidence::High,
+        });
+    }
+    let vbr_header = parser.state.vbr_header.as_ref();
+    if let Some(count) = vbr_header.and_then(|header| header.frames()) {
+        // Like in the full scan, the frame carrying the header is counted.
+        return Ok(DurationEstimate {
+            duration: frame_duration * count.saturating_add(1),
+            confidence: Confidence::High,
+        });
+    }
+    let vbr_offset = vbr_header.map(|header| header.offset);
+    let mut audio = frames
+        .iter()
+        .filter(|frame| Some(frame.offset) != vbr_offset);
+    let bitrate = audio.next().unwrap_or(first).bitrate;
+    let confidence = if audio.all(|frame| frame.bitrate == bitrate) {
+        Confidence::Medium
+    } else {
+        Confidence::Low
     };
+    let end = parser.end.unwrap_or(0);
+    let count = get_frame_count(
+        first.version,
+        first.layer,
+        bitrate as u32 * 1000,
+        first.sampling_freq,
+        end.saturating_sub(first.offset as u64),
+    );
+    Ok(DurationEstimate {
+        duration: frame_duration * count as u32,
+        confidence,
+    })
+}
+
+/// Number of bytes which must be available after a tag or a frame header to parse it.
+fn get_tag_size(buf: &[u8], options: &ParseOptions) -> usize {
+    if buf.len() >= 10 && buf.starts_with(b"ID3") {
+        let size = buf[6..10]
+            .iter()
+            .fold(0, |size, c| (size << 7) | (*c as usize & 0x7f));
+        if !(options.id3v2 && options.accepts_tag_size(size as u64 + 10)) {
+            // Skipped without being read.
+            return 0;
+        }
+        // Tag header, footer and extended header.
+        size + 64
+    } else if let Some(header) = ApeHeader::read(buf) {
+        header.total_size()
+    } else {
+        0
+    }
+}
+
+/// Returns the content from `pos` to `end`, holding at least the tag starting at `pos`
+/// if any and `lookahead` bytes.
+fn get_window<'a, S: Source>(
+    source: &'a mut S,
+    pos: u32,
+    end: u64,
+    lookahead: usize,
+    options: &ParseOptions,
+) -> Result<&'a [u8], Error> {
+    let tag_size = get_tag_size(source.read_at(pos as u64, lookahead)?, options);
+    let buf = source.read_at(pos as u64, lookahead + tag_size)?;
+    let len = end.saturating_sub(pos as u64).min(buf.len() as u64) as usize;
+    Ok(&buf[..len])
+}
+
+fn read_tags_from_source<S: Source>(
+    mut source: S,
+    options: &ParseOptions,
+) -> Result<MP3Tags, Error> {
+    let mut state = ParseState::default();
+    let end = read_trailing_tags(&mut source, &mut state, options)?;
     let mut i = 0u32;
+    while (i as u64) < end {
+        let buf = get_window(&mut source, i, end, 10, options)?;
+        if !buf.starts_with(b"ID3") {
+            break;
+        }
+        let old_i = i;
+        get_id3(&mut i, buf, old_i, &mut state, options)?;
+        if i == old_i {
+            break;
+        }
+    }
+    let mut tags = MP3Tags::default();
+    for event in state.ready.drain(..).chain(state.trailing.drain(..)) {
+        match event {
+            Event::Id3v1(v1) => tags.tag = Some(v1),
+            Event::Id3v2(v2) => tags.optional_info.push(*v2),
+            Event::Ape(tag) => tags.ape = Some(tag),
+            Event::Lyrics3(tag) => tags.lyrics3 = Some(tag),
+            Event::Frame(_) => {}
+        }
+    }
+    Ok(tags)
+}
+
+/// What the parser knows of the content already walked through.
+#[derive(Default)]
+pub(crate) struct ParseState {
+    /// Number of frames found so far.
+    frames: usize,
+    /// Last frame found, held back until the next one since an ID3v1 tag following it
+    /// can still change its size.
+    last: Option<Frame>,
+    /// Tags found after `last`.
+    held: Vec<Event>,
+    /// Items ready to be returned.
+    ready: VecDeque<Event>,
+    /// Tags stored at the end of the file, returned last.
+    trailing: Vec<Event>,
+    has_id3v1: bool,
+    has_ape: bool,
+    duration: Duration,
+    total_samples: u64,
+    vbr_header: Option<VbrHeader>,
+    crc_failures: u32,
+    resyncs: Vec<u32>,
+    summary: AudioSummary,
+    segments: Vec<Segment>,
+    /// Whether the scan stopped at `ParseOptions::max_frames`.
+    partial: bool,
+    /// Problems found so far, only recorded by a lenient parse.
+    diagnostics: Option<Vec<Diagnostic>>,
+    /// Start of the bytes being skipped because they are neither a frame nor a tag.
+    skipped: Option<u32>,
+}
+
+impl ParseState {
+    fn report<F>(&mut self, severity: Severity, offset: u64, kind: ErrorKind, message: F)
+    where
+        F: FnOnce() -> String,
+    {
+        if let Some(diagnostics) = &mut self.diagnostics {
+            diagnostics.push(Diagnostic {
+                severity,
+                offset,
+                kind,
+                message: message(),
+            });
+        }
+    }
+
+    /// Records the error of a lenient parse and goes on, or returns it.
+    fn recover<T>(&mut self, result: Result<T, Error>) -> Result<Option<T>, Error> {
+        match (result, &mut self.diagnostics) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(e), Some(diagnostics)) => {
+                diagnostics.push(Diagnostic::from_error(&e, 0));
+                Ok(None)
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    fn report_undecodable(&mut self, offset: u64, frames: Vec<String>) {
+        for frame in frames {
+            self.report(Severity::Warning, offset, ErrorKind::InvalidData, || {
+                format!("The {} frame of the ID3v2 tag can't be decoded", frame)
+            });
+        }
+    }
+
+    /// Ends the bytes being skipped, if any, at `end`.
+    fn end_skip(&mut self, end: u32) {
+        if let Some(start) = self.skipped.take() {
+            self.report(Severity::Warning, start as u64, ErrorKind::BadSync, || {
+                format!("{} bytes are neither a frame nor a tag", end - start)
+            });
+        }
+    }
+
+    fn push_tag(&mut self, event: Event) {
+        if self.last.is_some() {
+            self.held.push(event);
+        } else {
+            self.ready.push_back(event);
+        }
+    }
+
+    fn push_frame(&mut self, frame: Frame) {
+        if let Some(last) = self.last.take() {
+            self.ready.push_back(Event::Frame(last));
+        }
+        self.ready.extend(self.held.drain(..));
+        self.last = Some(frame);
+        self.frames += 1;
+    }
+}
+
+/// Walks through the content of a [`Source`], returning its frames and tags one by one.
+pub(crate) struct Parser<S> {
+    source: S,
+    options: ParseOptions,
+    state: ParseState,
+    /// Position of the parser in the content.
+    i: u32,
+    /// Where the audio ends, once the trailing tags have been read.
+    end: Option<u64>,
+    lookahead: usize,
+    error: Option<Error>,
+    finished: bool,
+}
+
+impl<S: Source> Parser<S> {
+    pub(crate) fn new(source: S, options: &ParseOptions) -> Parser<S> {
+        Parser {
+            source,
+            options: options.clone(),
+            state: ParseState::default(),
+            i: 0,
+            end: None,
+            // Enough to confirm a frame header with the following ones, whatever their
+            // size.
+            lookahead: (options.sync_frames as usize + 2) * (MAX_FREE_FORMAT_SIZE + 4),
+            error: None,
+            finished: false,
+        }
+    }
+
+    /// Records the problems found as diagnostics instead of failing, when possible.
+    pub(crate) fn lenient(mut self) -> Parser<S> {
+        self.state.diagnostics = Some(Vec::new());
+        self
+    }
 
-    'a: while i < buf.len() as u32 {
+    /// Moves the parser past the next tag or frame, or by one byte if there is none.
+    fn step(&mut self) -> Result<(), Error> {
+        let end = match self.end {
+            Some(end) => end,
+            None => {
+                let end = read_trailing_tags(&mut self.source, &mut self.state, &self.options)?;
+                self.end = Some(end);
+                end
+            }
+        };
+        if self.i as u64 >= end {
+            return self.finish();
+        }
+        if let Some(max_frames) = self.options.max_frames {
+            if self.state.frames >= max_frames as usize {
+                self.state.partial = true;
+                self.flush();
+                return Ok(());
+            }
+        }
+        let base = self.i;
+        let buf = get_window(&mut self.source, base, end, self.lookahead, &self.options)?;
+        get_id3(&mut self.i, buf, base, &mut self.state, &self.options)?;
+        if self.i != base {
+            self.state.end_skip(base);
+        }
+        if self.i as u64 + 3 >= end {
+            return self.finish();
+        }
+        let base = self.i;
+        let buf = get_window(&mut self.source, base, end, self.lookahead, &self.options)?;
+        if read_header(buf, base, &mut self.i, &mut self.state, &self.options)? {
+            self.state.end_skip(base);
+            return Ok(());
+        }
+        let old_i = self.i;
+        get_id3(&mut self.i, buf, base, &mut self.state, &self.options)?;
+        if self.i == old_i {
+            if self.state.skipped.is_none() {
+                self.state.skipped = Some(old_i);
+            }
+            self.i += 1;
+        } else {
+            self.state.end_skip(base);
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.finished = true;
+        let end = self.end.unwrap_or(0);
+        let state = &mut self.state;
+        state.end_skip(self.i.min(end as u32));
+        let last = state
+            .last
+            .as_ref()
+            .map(|last| (last.offset as u64, last.size));
+        if let Some((offset, size)) = last {
+            let frame_end = offset + size as u64;
+            if frame_end > end {
+                state.report(Severity::Warning, offset, ErrorKind::Truncated, || {
+                    format!("The last frame lacks {} bytes", frame_end - end)
+                });
+            }
+            if !state.has_id3v1 && self.i <= size {
+                state.recover::<()>(Err(Error::new(ErrorKind::InvalidData)
+                    .with_offset(offset)
+                    .with_context("frame")))?;
+            }
+        }
+        self.flush();
+        Ok(())
+    }
+
+    /// Makes the items held back ready to be returned and ends the iteration.
+    fn flush(&mut self) {
+        self.finished = true;
+        let state = &mut self.state;
+        if let Some(last) = state.last.take() {
+            state.ready.push_back(Event::Frame(last));
+        }
+        state.ready.extend(state.held.drain(..));
+        for mut event in state.trailing.drain(..) {
+            if let Event::Id3v2(tag) = &mut event {
+                tag.position = state.frames as u32;
+            }
+            state.ready.push_back(event);
+        }
+    }
+}
+
+impl<S: Source> Iterator for Parser<S> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Result<Event, Error>> {
         loop {
-            get_id3(&mut i, buf, &mut meta)?;
-            if i + 3 >= buf.len() as u32 {
-                break 'a;
+            if let Some(event) = self.state.ready.pop_front() {
+                return Some(Ok(event));
             }
-            match read_header(buf, &mut i, &mut meta) {
-                Ok(true) => continue 'a,
-                Err(e) => return Err(e),
-                _ => {}
+            if let Some(e) = self.error.take() {
+                return Some(Err(e));
             }
-            let old_i = i;
-            get_id3(&mut i, buf, &mut meta)?;
-            if i == old_i {
-                i += 1;
+            if self.finished {
+                return None;
             }
-            if i >= buf.len() as u32 {
-                break 'a;
+            if let Err(e) = self.step() {
+                match &mut self.state.diagnostics {
+                    Some(diagnostics) => {
+                        // Keep what has been found so far.
+                        diagnostics.push(Diagnostic::from_error(&e, self.i as u64));
+                        self.flush();
+                    }
+                    None => {
+                        self.finished = true;
+                        self.error = Some(e);
+                    }
+                }
             }
         }
     }
-    if meta.tag.is_none() {
-        if let Some(last) = meta.frames.last_mut() {
-            if i <= last.size {
-                return Err(Error::InvalidData);
+}
+
+fn read_from_source<S: Source>(
+    source: S,
+    options: &ParseOptions,
+    lenient: bool,
+) -> Result<(MP3Metadata, Vec<Diagnostic>), Error> {
+    let mut parser = Parser::new(source, options);
+    if lenient {
+        parser = parser.lenient();
+    }
+    let mut frames = Vec::new();
+    let mut tag = None;
+    let mut optional_info = Vec::new();
+    let mut ape = None;
+    let mut lyrics3 = None;
+    for event in &mut parser {
+        match event? {
+            Event::Frame(frame) => {
+                if options.frames {
+                    frames.push(frame);
+                }
             }
+            Event::Id3v1(v1) => tag = Some(v1),
+            Event::Id3v2(v2) => optional_info.push(*v2),
+            Event::Ape(tag) => ape = Some(tag),
+            Event::Lyrics3(tag) => lyrics3 = Some(tag),
         }
     }
-    if meta.frames.is_empty() {
-        Err(Error::NotMP3)
+    let state = parser.state;
+    let mut meta = MP3Metadata {
+        frames,
+        duration: state.duration,
+        total_samples: state.total_samples,
+        tag,
+        optional_info,
+        ape,
+        lyrics3,
+        vbr_header: state.vbr_header,
+        crc_failures: state.crc_failures,
+        resyncs: state.resyncs,
+        summary: state.summary,
+        segments: state.segments,
+        partial: state.partial,
+    };
+    meta.summary.classify(meta.vbr_header.as_ref());
+    if state.frames == 0 && !meta.resyncs.is_empty() {
+        Err(Error::new(ErrorKind::BadSync).with_offset(meta.resyncs[0] as u64))
+    } else if state.frames == 0 {
+        Err(Error::new(ErrorKind::NotMP3))
     } else {
-        Ok(meta)
+        Ok((meta, state.diagnostics.unwrap_or_default()))
     }
 }
 
@@ -538,7 +1669,7 @@ mod tests {
 
         match ret {
             Ok(_) => panic!("Wasn't supposed to be ok!"),
-            Err(e) => assert_eq!(e, Error::NotMP3),
+            Err(e) => assert_eq!(e.kind(), ErrorKind::NotMP3),
         }
     }
 
@@ -548,7 +1679,7 @@ mod tests {
 
         match ret {
             Ok(_) => panic!("Wasn't supposed to be ok!"),
-            Err(e) => assert_eq!(e, Error::DuplicatedIDV3),
+            Err(e) => assert_eq!(e.kind(), ErrorKind::DuplicatedIDV3),
         }
     }
 
@@ -0,0 +1,144 @@
+use crate::enums::Codepage;
+
+/// Settings used by [`read_from_file_with`](crate::read_from_file_with),
+/// [`read_from_slice_with`](crate::read_from_slice_with) and the other `_with` functions.
+///
+/// ```
+/// use mp3_metadata::{Codepage, ParseOptions};
+///
+/// let options = ParseOptions::new().codepage(Codepage::Windows1251);
+/// // Only the tags and the first 100 frames.
+/// let options = ParseOptions::new().frames(false).max_frames(100);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseOptions {
+    pub(crate) codepage: Codepage,
+    pub(crate) side_info: bool,
+    pub(crate) sync_frames: u32,
+    pub(crate) frames: bool,
+    pub(crate) id3v1: bool,
+    pub(crate) id3v2: bool,
+    pub(crate) ape: bool,
+    pub(crate) lyrics3: bool,
+    pub(crate) max_frames: Option<u32>,
+    pub(crate) max_tag_size: Option<u32>,
+    pub(crate) duplicate_id3v1_error: bool,
+    pub(crate) bad_tag_error: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            codepage: Codepage::Latin1,
+            side_info: false,
+            sync_frames: 1,
+            frames: true,
+            id3v1: true,
+            id3v2: true,
+            ape: true,
+            lyrics3: true,
+            max_frames: None,
+            max_tag_size: None,
+            duplicate_id3v1_error: true,
+            bad_tag_error: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn new() -> ParseOptions {
+        ParseOptions::default()
+    }
+
+    /// Codepage used to decode the ID3v1 text fields. Defaults to ISO-8859-1.
+    pub fn codepage(mut self, codepage: Codepage) -> ParseOptions {
+        self.codepage = codepage;
+        self
+    }
+
+    /// Whether to decode the side information of Layer III frames into
+    /// [`Frame::side_info`](crate::Frame::side_info). Defaults to `false`.
+    pub fn side_info(mut self, side_info: bool) -> ParseOptions {
+        self.side_info = side_info;
+        self
+    }
+
+    /// Number of frames which must follow a frame header found while (re)synchronizing
+    /// for it to be accepted, at the offset given by its length and with the same
+    /// version, layer and sampling frequency. The end of the data or a tag also confirm
+    /// the header. Defaults to 1; 0 accepts every header.
+    pub fn sync_frames(mut self, sync_frames: u32) -> ParseOptions {
+        self.sync_frames = sync_frames;
+        self
+    }
+
+    /// Whether to keep the frames in [`MP3Metadata::frames`](crate::MP3Metadata::frames).
+    /// They are still parsed to compute the duration and the statistics. Defaults to
+    /// `true`.
+    pub fn frames(mut self, frames: bool) -> ParseOptions {
+        self.frames = frames;
+        self
+    }
+
+    /// Whether to read ID3v1 tags. Defaults to `true`.
+    pub fn id3v1(mut self, id3v1: bool) -> ParseOptions {
+        self.id3v1 = id3v1;
+        self
+    }
+
+    /// Whether to read ID3v2 tags. Defaults to `true`.
+    pub fn id3v2(mut self, id3v2: bool) -> ParseOptions {
+        self.id3v2 = id3v2;
+        self
+    }
+
+    /// Whether to read APE tags. Defaults to `true`.
+    pub fn ape(mut self, ape: bool) -> ParseOptions {
+        self.ape = ape;
+        self
+    }
+
+    /// Whether to read Lyrics3 tags. Defaults to `true`.
+    pub fn lyrics3(mut self, lyrics3: bool) -> ParseOptions {
+        self.lyrics3 = lyrics3;
+        self
+    }
+
+    /// Stops after this many frames; the duration, the number of samples and the
+    /// statistics then only cover them. The tags at the end of the file are still read.
+    /// Unlimited by default.
+    pub fn max_frames(mut self, max_frames: u32) -> ParseOptions {
+        self.max_frames = Some(max_frames);
+        self
+    }
+
+    /// Size in bytes above which ID3v2, APE and Lyrics3 tags are skipped, when it is
+    /// known without reading them. Unlimited by default.
+    pub fn max_tag_size(mut self, max_tag_size: u32) -> ParseOptions {
+        self.max_tag_size = Some(max_tag_size);
+        self
+    }
+
+    /// Whether finding a second ID3v1 tag is an error of kind
+    /// [`ErrorKind::DuplicatedIDV3`](crate::ErrorKind::DuplicatedIDV3).
+    /// Otherwise the extra ones are ignored, the one at the end of the file coming first.
+    /// Defaults to `true`.
+    pub fn duplicate_id3v1_error(mut self, duplicate_id3v1_error: bool) -> ParseOptions {
+        self.duplicate_id3v1_error = duplicate_id3v1_error;
+        self
+    }
+
+    /// Whether a tag with an inconsistent header or an unsupported version is an error of
+    /// kind [`ErrorKind::BadTagHeader`](crate::ErrorKind::BadTagHeader) or
+    /// [`ErrorKind::UnsupportedVersion`](crate::ErrorKind::UnsupportedVersion).
+    /// Otherwise it is skipped. Defaults to `false`.
+    pub fn bad_tag_error(mut self, bad_tag_error: bool) -> ParseOptions {
+        self.bad_tag_error = bad_tag_error;
+        self
+    }
+
+    pub(crate) fn accepts_tag_size(&self, size: u64) -> bool {
+        self.max_tag_size
+            .map_or(true, |max_tag_size| size <= max_tag_size as u64)
+    }
+}
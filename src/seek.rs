@@ -0,0 +1,283 @@
+use std::time::Duration;
+
+use crate::enums::VbrHeaderKind;
+use crate::types::{Frame, MpegLocationLookupTable, VbrHeader};
+
+/// Position in the stream returned by a [`SeekIndex`] lookup.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SeekResult {
+    /// Byte offset in the file.
+    pub offset: u64,
+    pub time: Duration,
+    /// `true` if `offset` and `time` are those of a frame, `false` if they were
+    /// interpolated between the points of a seek table.
+    pub exact: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct SeekPoint {
+    time: Duration,
+    offset: u64,
+}
+
+/// Maps times to byte offsets and back.
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// let meta = mp3_metadata::read_from_file("file.mp3").expect("read_from_file failed");
+/// if let Some(index) = meta.seek_index() {
+///     let position = index.offset_for_time(Duration::from_secs(30));
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeekIndex {
+    points: Vec<SeekPoint>,
+    /// End of the stream, used to interpolate after the last point.
+    end: Option<SeekPoint>,
+    /// Whether each point is a frame.
+    exact: bool,
+    /// Whether the points cover the whole stream. Lookups after the last one aren't
+    /// exact otherwise.
+    complete: bool,
+}
+
+fn scale(value: u64, numerator: Duration, denominator: Duration) -> u64 {
+    let denominator = denominator.as_nanos();
+    if denominator == 0 {
+        return 0;
+    }
+    (value as u128 * numerator.as_nanos() / denominator) as u64
+}
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    )
+}
+
+impl SeekIndex {
+    /// Builds an exact index from the frames of the stream.
+    pub fn from_frames(frames: &[Frame]) -> Option<SeekIndex> {
+        let last = frames.last()?;
+        Some(SeekIndex {
+            points: frames
+                .iter()
+                .map(|frame| SeekPoint {
+                    time: frame.position,
+                    offset: frame.offset as u64,
+                })
+                .collect(),
+            end: Some(SeekPoint {
+                time: last.position + last.duration.unwrap_or_default(),
+                offset: last.offset as u64 + last.size as u64,
+            }),
+            exact: true,
+            complete: true,
+        })
+    }
+
+    /// Marks the index as covering only the start of the stream.
+    pub(crate) fn partial(mut self) -> SeekIndex {
+        self.complete = false;
+        self
+    }
+
+    /// Returns the end of the points when `past_end` and the index doesn't cover the
+    /// whole stream: what follows is unknown.
+    fn uncovered(&self, past_end: impl Fn(SeekPoint) -> bool) -> Option<SeekResult> {
+        match self.end {
+            Some(end) if !self.complete && past_end(end) => Some(SeekResult {
+                offset: end.offset,
+                time: end.time,
+                exact: false,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Builds an index from the table of contents of a Xing or VBRI header. Xing
+    /// headers need the number of frames, the number of bytes and the TOC.
+    pub fn from_vbr_header(header: &VbrHeader) -> Option<SeekIndex> {
+        let duration = header.duration()?;
+        let bytes = header.bytes()? as u64;
+        let start = header.offset as u64;
+        let points = match header.kind {
+            VbrHeaderKind::Xing(ref xing) => {
+                // Entry `i` is the position, in 1/256th of the stream, at `i`% of its
+                // duration.
+                let toc = xing.toc.as_ref()?;
+                toc.iter()
+                    .enumerate()
+                    .map(|(i, entry)| SeekPoint {
+                        time: duration_from_nanos(duration.as_nanos() * i as u128 / 100),
+                        offset: start + *entry as u64 * bytes / 256,
+                    })
+                    .collect()
+            }
+            VbrHeaderKind::Vbri(ref vbri) => {
+                // Entries are the sizes of groups of frames following the VBRI frame.
+                let entry_duration = duration_from_nanos(
+                    vbri.frames_per_entry as u128
+                        * header.samples_per_frame as u128
+                        * 1_000_000_000
+                        / header.sampling_freq.max(1) as u128,
+                );
+                let table_size = vbri.toc.iter().map(|size| *size as u64).sum::<u64>();
+                let mut point = SeekPoint {
+                    time: Duration::new(0, 0),
+                    offset: start + bytes.saturating_sub(table_size),
+                };
+                let mut points = vec![point];
+                for size in vbri.toc.iter() {
+                    point.time += entry_duration;
+                    point.offset += *size as u64;
+                    points.push(point);
+                }
+                points
+            }
+        };
+        Some(SeekIndex {
+            points,
+            end: Some(SeekPoint {
+                time: duration,
+                offset: start + bytes,
+            }),
+            exact: false,
+            complete: true,
+        })
+    }
+
+    /// Builds an index from an ID3v2 "MLLT" frame. `start` is the offset of the first
+    /// frame.
+    pub fn from_mllt(table: &MpegLocationLookupTable, start: u64) -> Option<SeekIndex> {
+        let mut point = SeekPoint {
+            time: Duration::new(0, 0),
+            offset: start,
+        };
+        let mut points = vec![point];
+        for reference in table.references.iter() {
+            point.time += Duration::from_millis(
+                table.millis_between_reference as u64 + reference.millis_deviation as u64,
+            );
+            point.offset += table.bytes_between_reference as u64 + reference.bytes_deviation as u64;
+            points.push(point);
+        }
+        if points.len() < 2 {
+            return None;
+        }
+        Some(SeekIndex {
+            points,
+            end: None,
+            exact: false,
+            complete: true,
+        })
+    }
+
+    /// Whether the index holds every frame, so that lookups are exact.
+    pub fn is_exact(&self) -> bool {
+        self.exact && self.complete
+    }
+
+    /// Returns the next point after `index`, or the end of the stream.
+    fn next(&self, index: usize) -> Option<SeekPoint> {
+        self.points.get(index + 1).cloned().or(self.end)
+    }
+
+    /// Position to seek to in order to play from `time`: the start of the frame
+    /// containing it for an exact index.
+    pub fn offset_for_time(&self, time: Duration) -> Option<SeekResult> {
+        if let Some(result) = self.uncovered(|end| time >= end.time) {
+            return Some(result);
+        }
+        let index = self
+            .points
+            .partition_point(|point| point.time <= time)
+            .max(1)
+            - 1;
+        let point = *self.points.get(index)?;
+        if self.exact || time <= point.time {
+            return Some(SeekResult {
+                offset: point.offset,
+                time: point.time,
+                exact: self.exact,
+            });
+        }
+        let next = match self.next(index) {
+            Some(next) if next.time > point.time => next,
+            _ => {
+                return Some(SeekResult {
+                    offset: point.offset,
+                    time: point.time,
+                    exact: false,
+                })
+            }
+        };
+        let time = time.min(next.time);
+        Some(SeekResult {
+            offset: point.offset
+                + scale(
+                    next.offset - point.offset,
+                    time - point.time,
+                    next.time - point.time,
+                ),
+            time,
+            exact: false,
+        })
+    }
+
+    /// Time at which the data at `offset` is played: the start of the frame containing
+    /// it for an exact index.
+    pub fn time_for_offset(&self, offset: u64) -> Option<SeekResult> {
+        if let Some(result) = self.uncovered(|end| offset >= end.offset) {
+            return Some(result);
+        }
+        let index = self
+            .points
+            .partition_point(|point| point.offset <= offset)
+            .max(1)
+            - 1;
+        let point = *self.points.get(index)?;
+        if self.exact || offset <= point.offset {
+            return Some(SeekResult {
+                offset: point.offset,
+                time: point.time,
+                exact: self.exact,
+            });
+        }
+        let next = match self.next(index) {
+            Some(next) if next.offset > point.offset => next,
+            _ => {
+                return Some(SeekResult {
+                    offset: point.offset,
+                    time: point.time,
+                    exact: false,
+                })
+            }
+        };
+        let offset = offset.min(next.offset);
+        let nanos = (next.time - point.time).as_nanos() * (offset - point.offset) as u128
+            / (next.offset - point.offset) as u128;
+        Some(SeekResult {
+            offset,
+            time: point.time + duration_from_nanos(nanos),
+            exact: false,
+        })
+    }
+
+    /// Index in [`MP3Metadata::frames`](crate::MP3Metadata::frames) of the frame playing at
+    /// `time`. Only available for an exact index and a time it covers.
+    pub fn frame_at(&self, time: Duration) -> Option<usize> {
+        if !self.exact || self.points.is_empty() || self.uncovered(|end| time >= end.time).is_some()
+        {
+            return None;
+        }
+        Some(
+            self.points
+                .partition_point(|point| point.time <= time)
+                .max(1)
+                - 1,
+        )
+    }
+}
@@ -0,0 +1,91 @@
+use crate::enums::{BlockType, ChannelType, Layer, Version, CRC};
+use crate::types::{Frame, Granule, SideInfo};
+use crate::utils::{get_side_info_size, BitReader};
+
+/// Decodes the side information of a Layer III frame. `buf` starts with the frame
+/// header.
+pub fn read_side_info(buf: &[u8], frame: &Frame) -> Option<SideInfo> {
+    if frame.layer != Layer::Layer3 {
+        return None;
+    }
+    let start = if frame.crc == CRC::Added { 6 } else { 4 };
+    let size = get_side_info_size(frame.version, frame.chan_type);
+    let mut reader = BitReader::new(buf.get(start..start + size)?);
+    let channels = match frame.chan_type {
+        ChannelType::SingleChannel => 1,
+        _ => 2,
+    };
+    let mpeg1 = frame.version == Version::MPEG1;
+
+    let (main_data_begin, private_bits) = if mpeg1 {
+        (
+            reader.read(9)?,
+            reader.read(if channels == 1 { 5 } else { 3 })?,
+        )
+    } else {
+        (
+            reader.read(8)?,
+            reader.read(if channels == 1 { 1 } else { 2 })?,
+        )
+    };
+    let mut scfsi = Vec::new();
+    if mpeg1 {
+        for _ in 0..channels {
+            let bits = reader.read(4)?;
+            scfsi.push([bits & 8 != 0, bits & 4 != 0, bits & 2 != 0, bits & 1 != 0]);
+        }
+    }
+    let mut granules = Vec::new();
+    for _ in 0..if mpeg1 { 2 } else { 1 } {
+        let mut granule = Vec::new();
+        for _ in 0..channels {
+            granule.push(read_granule(&mut reader, mpeg1)?);
+        }
+        granules.push(granule);
+    }
+    Some(SideInfo {
+        main_data_begin: main_data_begin as u16,
+        private_bits: private_bits as u8,
+        scfsi,
+        granules,
+    })
+}
+
+fn read_granule(reader: &mut BitReader, mpeg1: bool) -> Option<Granule> {
+    let mut granule = Granule {
+        part2_3_length: reader.read(12)? as u16,
+        big_values: reader.read(9)? as u16,
+        global_gain: reader.read(8)? as u8,
+        scalefac_compress: reader.read(if mpeg1 { 4 } else { 9 })? as u16,
+        ..Default::default()
+    };
+    if reader.read(1)? == 1 {
+        granule.block_type = BlockType::from(reader.read(2)?);
+        granule.mixed_block = reader.read(1)? == 1;
+        for table in granule.table_select.iter_mut().take(2) {
+            *table = reader.read(5)? as u8;
+        }
+        for gain in granule.subblock_gain.iter_mut() {
+            *gain = reader.read(3)? as u8;
+        }
+        // Implicit with window switching.
+        granule.region0_count = if granule.block_type == BlockType::Short && !granule.mixed_block {
+            8
+        } else {
+            7
+        };
+        granule.region1_count = 36;
+    } else {
+        for table in granule.table_select.iter_mut() {
+            *table = reader.read(5)? as u8;
+        }
+        granule.region0_count = reader.read(4)? as u8;
+        granule.region1_count = reader.read(3)? as u8;
+    }
+    if mpeg1 {
+        granule.preflag = reader.read(1)? == 1;
+    }
+    granule.scalefac_scale = reader.read(1)? == 1;
+    granule.count1table_select = reader.read(1)? == 1;
+    Some(granule)
+}
@@ -0,0 +1,115 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::enums::ErrorKind;
+use crate::types::Error;
+
+/// Random access to the content of a file.
+pub trait Source {
+    /// Length in bytes of the content.
+    fn len(&self) -> u64;
+
+    /// Returns the content from `pos`: at least `min` bytes, unless the end is reached
+    /// first, and possibly more.
+    fn read_at(&mut self, pos: u64, min: usize) -> Result<&[u8], Error>;
+}
+
+/// Content already in memory.
+pub struct SliceSource<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> SliceSource<'a> {
+    pub fn new(buf: &'a [u8]) -> SliceSource<'a> {
+        SliceSource { buf }
+    }
+}
+
+impl<'a> Source for SliceSource<'a> {
+    fn len(&self) -> u64 {
+        self.buf.len() as u64
+    }
+
+    fn read_at(&mut self, pos: u64, _min: usize) -> Result<&[u8], Error> {
+        Ok(&self.buf[(pos as usize).min(self.buf.len())..])
+    }
+}
+
+/// Bytes read at once by a [`ReaderSource`], unless more are needed.
+const CHUNK_SIZE: usize = 256 * 1024;
+
+/// Content read on demand, through a buffer holding only the part being parsed.
+pub struct ReaderSource<R> {
+    reader: R,
+    len: u64,
+    buf: Vec<u8>,
+    /// Position of `buf` in the content.
+    start: u64,
+    chunk_size: usize,
+}
+
+impl<R: Read + Seek> ReaderSource<R> {
+    pub fn new(reader: R) -> Result<ReaderSource<R>, Error> {
+        ReaderSource::with_chunk_size(reader, CHUNK_SIZE)
+    }
+
+    /// Reads `chunk_size` bytes at once instead of [`CHUNK_SIZE`], unless more are needed.
+    pub fn with_chunk_size(mut reader: R, chunk_size: usize) -> Result<ReaderSource<R>, Error> {
+        let len = reader
+            .seek(SeekFrom::End(0))
+            .map_err(|e| Error::new(ErrorKind::FileError).with_source(e))?;
+        Ok(ReaderSource {
+            reader,
+            len,
+            buf: Vec::new(),
+            start: 0,
+            chunk_size,
+        })
+    }
+}
+
+impl<R: Read + Seek> Source for ReaderSource<R> {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn read_at(&mut self, pos: u64, min: usize) -> Result<&[u8], Error> {
+        let pos = pos.min(self.len);
+        let wanted = (self.len - pos).min(min as u64) as usize;
+        let end = self.start + self.buf.len() as u64;
+        if pos < self.start || pos + wanted as u64 > end {
+            let size = (self.len - pos).min(min.max(self.chunk_size) as u64) as usize;
+            self.buf.resize(size, 0);
+            // Don't hold on to the memory of a read larger than a chunk.
+            self.buf.shrink_to(self.chunk_size);
+            self.reader
+                .seek(SeekFrom::Start(pos))
+                .and_then(|_| self.reader.read_exact(&mut self.buf))
+                .map_err(|e| {
+                    let kind = if e.kind() == io::ErrorKind::UnexpectedEof {
+                        ErrorKind::Truncated
+                    } else {
+                        ErrorKind::FileError
+                    };
+                    Error::new(kind).with_offset(pos).with_source(e)
+                })?;
+            self.start = pos;
+        }
+        Ok(&self.buf[(pos - self.start) as usize..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn shrink_after_large_read() {
+        let mut source =
+            ReaderSource::with_chunk_size(Cursor::new(vec![0; 1000]), 16).expect("new failed");
+        assert_eq!(source.read_at(0, 500).expect("read failed").len(), 500);
+        assert!(source.buf.capacity() >= 500);
+        assert_eq!(source.read_at(600, 1).expect("read failed").len(), 16);
+        assert!(source.buf.capacity() < 500);
+    }
+}
@@ -1,6 +1,17 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::ops::Range;
 use std::time::Duration;
 
-use crate::enums::{ChannelType, Copyright, Emphasis, Genre, Layer, Status, Version, CRC};
+use crate::ancillary::{read_ancillary_data, AncillaryData};
+use crate::enums::{
+    ApeItemValue, BitrateMode, BlockType, ChannelType, Confidence, Copyright, Emphasis, ErrorKind,
+    GaplessSource, Genre, LameVbrMethod, Layer, Lyrics3Version, ModeExtension, Severity, Status,
+    VbrHeaderKind, Version, CRC,
+};
+use crate::seek::SeekIndex;
+use crate::utils::get_main_data_size;
 
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct Frame {
@@ -13,22 +24,550 @@ pub struct Frame {
     pub padding: bool,
     pub private_bit: bool,
     pub chan_type: ChannelType,
-    pub intensity_stereo: bool,
-    pub ms_stereo: bool,
+    /// Only set for joint stereo frames.
+    pub mode_extension: Option<ModeExtension>,
     pub copyright: Copyright,
     pub status: Status,
     pub emphasis: Emphasis,
     pub duration: Option<Duration>,
     pub position: Duration,
     pub offset: u32,
+    /// Index of the first sample of the frame in the stream.
+    pub first_sample: u64,
+    /// Checksum following the header, for CRC-protected frames.
+    pub crc_checksum: Option<u16>,
+    /// Whether `crc_checksum` matches the protected bits of the frame, `None` if the
+    /// frame isn't protected or is truncated.
+    pub crc_valid: Option<bool>,
+    /// Layer III side information, only decoded when enabled with
+    /// [`ParseOptions::side_info`](crate::ParseOptions::side_info).
+    pub side_info: Option<SideInfo>,
+}
+
+/// Side information of a Layer III frame, following the header and the CRC.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SideInfo {
+    /// Negative offset, in bytes from the frame header, of the start of the main data in
+    /// the bit reservoir.
+    pub main_data_begin: u16,
+    pub private_bits: u8,
+    /// Scale factor selection information of each channel and scale factor band group.
+    /// Empty for MPEG-2 and MPEG-2.5.
+    pub scfsi: Vec<[bool; 4]>,
+    /// Side information of each granule (two for MPEG-1, one otherwise) and channel.
+    pub granules: Vec<Vec<Granule>>,
+}
+
+/// Side information of one channel of a Layer III granule.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Granule {
+    /// Number of bits used for the scale factors and the Huffman data.
+    pub part2_3_length: u16,
+    pub big_values: u16,
+    pub global_gain: u8,
+    pub scalefac_compress: u16,
+    pub block_type: BlockType,
+    pub mixed_block: bool,
+    pub table_select: [u8; 3],
+    pub subblock_gain: [u8; 3],
+    pub region0_count: u8,
+    pub region1_count: u8,
+    /// Always `false` for MPEG-2 and MPEG-2.5, where it isn't transmitted.
+    pub preflag: bool,
+    pub scalefac_scale: bool,
+    pub count1table_select: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct MP3Metadata {
     pub duration: Duration,
+    /// Number of samples (per channel) of all the frames, the encoder delay and padding
+    /// as well as the frame carrying the VBR header included.
+    pub total_samples: u64,
     pub frames: Vec<Frame>,
     pub tag: Option<AudioTag>,
     pub optional_info: Vec<OptionalAudioTags>,
+    pub ape: Option<ApeTag>,
+    pub lyrics3: Option<Lyrics3Tag>,
+    pub vbr_header: Option<VbrHeader>,
+    /// Number of CRC-protected frames whose checksum doesn't match.
+    pub crc_failures: u32,
+    /// Offsets of the candidate frame headers rejected because the following frames
+    /// didn't confirm them, see [`ParseOptions::sync_frames`](crate::ParseOptions::sync_frames).
+    pub resyncs: Vec<u32>,
+    pub summary: AudioSummary,
+    /// Runs of frames sharing the same version, layer, sampling frequency and channel
+    /// type.
+    pub segments: Vec<Segment>,
+    /// Whether the scan stopped at [`ParseOptions::max_frames`](crate::ParseOptions::max_frames),
+    /// so that the frames only cover the start of the stream.
+    pub partial: bool,
+}
+
+/// Tags of a file, read without going through its frames by
+/// [`read_tags_from_file`](crate::read_tags_from_file).
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct MP3Tags {
+    pub tag: Option<AudioTag>,
+    /// ID3v2 tags found at the start of the file, then the one appended to it. The
+    /// position of the latter is unknown and left at 0.
+    pub optional_info: Vec<OptionalAudioTags>,
+    pub ape: Option<ApeTag>,
+    pub lyrics3: Option<Lyrics3Tag>,
+}
+
+/// Duration of a file computed from a few frames, see
+/// [`estimate_duration_from_file`](crate::estimate_duration_from_file).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DurationEstimate {
+    pub duration: Duration,
+    pub confidence: Confidence,
+}
+
+/// VBR header stored in the first frame of the stream, in place of audio data.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VbrHeader {
+    /// Offset of the frame carrying the header.
+    pub offset: u32,
+    pub sampling_freq: u16,
+    pub samples_per_frame: u32,
+    pub kind: VbrHeaderKind,
+}
+
+impl VbrHeader {
+    /// Number of audio frames, the one carrying the header excluded.
+    pub fn frames(&self) -> Option<u32> {
+        match self.kind {
+            VbrHeaderKind::Xing(ref xing) => xing.frames,
+            VbrHeaderKind::Vbri(ref vbri) => Some(vbri.frames),
+        }
+    }
+
+    /// Number of bytes of the stream, the frame carrying the header included.
+    pub fn bytes(&self) -> Option<u32> {
+        match self.kind {
+            VbrHeaderKind::Xing(ref xing) => xing.bytes,
+            VbrHeaderKind::Vbri(ref vbri) => Some(vbri.bytes),
+        }
+    }
+
+    /// Duration of the stream computed from the number of frames.
+    pub fn duration(&self) -> Option<Duration> {
+        if self.sampling_freq == 0 {
+            return None;
+        }
+        let samples = self.frames()? as u64 * self.samples_per_frame as u64;
+        let nanos = samples * 1_000_000_000 / self.sampling_freq as u64;
+        Some(Duration::new(
+            nanos / 1_000_000_000,
+            (nanos % 1_000_000_000) as u32,
+        ))
+    }
+}
+
+/// "Xing" header, called "Info" when the stream is CBR.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct XingHeader {
+    /// `true` for an "Info" header.
+    pub info: bool,
+    pub frames: Option<u32>,
+    pub bytes: Option<u32>,
+    /// Seek table: entry `i` gives the position at `i`% of the duration, as a fraction
+    /// of `bytes` scaled to 256.
+    pub toc: Option<[u8; 100]>,
+    /// From 0 (best) to 100 (worst).
+    pub quality: Option<u32>,
+    /// LAME extension, written right after the Xing header.
+    pub lame: Option<LameTag>,
+}
+
+/// "VBRI" header, written by the Fraunhofer encoders.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VbriHeader {
+    pub version: u16,
+    pub delay: u16,
+    pub quality: u16,
+    pub bytes: u32,
+    pub frames: u32,
+    /// Number of frames covered by each entry of `toc`.
+    pub frames_per_entry: u16,
+    /// Seek table: size in bytes of each group of `frames_per_entry` frames, already
+    /// multiplied by the scale factor.
+    pub toc: Vec<u32>,
+}
+
+/// Extension written by LAME (and FFmpeg) after the Xing header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LameTag {
+    /// Encoder short version string, like "LAME3.99r".
+    pub encoder: String,
+    pub revision: u8,
+    pub vbr_method: LameVbrMethod,
+    /// Lowpass filter value, in Hz.
+    pub lowpass: u32,
+    /// Peak signal amplitude, as a fixed-point number where 1.0 is `1 << 23`. See
+    /// [`LameTag::peak`].
+    pub peak_amplitude: u32,
+    /// "Radio" ReplayGain.
+    pub track_gain: Option<ReplayGain>,
+    /// "Audiophile" ReplayGain.
+    pub album_gain: Option<ReplayGain>,
+    /// `--nspsytune`, `--nssafejoint`, `--nogap` (next) and `--nogap` (previous) flags,
+    /// from the highest bit to the lowest.
+    pub encoding_flags: u8,
+    pub ath_type: u8,
+    /// Bitrate in kb/s: the target for ABR, the bitrate for CBR and the minimum for VBR.
+    /// 255 means 255 kb/s or more.
+    pub bitrate: u8,
+    /// Number of samples added by the encoder at the start of the stream.
+    pub encoder_delay: u16,
+    /// Number of samples added by the encoder at the end of the stream.
+    pub padding: u16,
+    pub noise_shaping: u8,
+    /// 0 for mono, 1 for stereo, 2 for dual channel, 3 for joint stereo, 4 for forced
+    /// joint stereo, 5 for auto and 6 for intensity stereo.
+    pub stereo_mode: u8,
+    pub unwise_settings: bool,
+    /// 0 for 32 kHz or less, 1 for 44.1 kHz, 2 for 48 kHz and 3 for more than 48 kHz.
+    pub source_sample_rate: u8,
+    /// MP3Gain change, in steps of 1.5 dB.
+    pub mp3_gain: i8,
+    pub surround: u8,
+    pub preset: u16,
+    /// Size of the stream in bytes, the frame carrying the tag included.
+    pub music_length: u32,
+    /// CRC-16 of the audio data.
+    pub music_crc: u16,
+    /// CRC-16 of the frame carrying the tag, up to this field.
+    pub tag_crc: u16,
+    /// Whether `tag_crc` matches the frame content.
+    pub crc_valid: bool,
+}
+
+impl LameTag {
+    /// Peak signal amplitude, 1.0 being the maximum.
+    pub fn peak(&self) -> f32 {
+        self.peak_amplitude as f32 / (1 << 23) as f32
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ReplayGain {
+    /// 1 when set by the artist, 2 by the user, 3 by a model and 4 by simple RMS
+    /// average.
+    pub originator: u8,
+    /// Gain adjustment in tenths of dB.
+    pub adjustment: i16,
+}
+
+impl ReplayGain {
+    pub(crate) fn read(value: u16) -> Option<ReplayGain> {
+        // Name code: 0 means not set.
+        if value >> 13 == 0 {
+            return None;
+        }
+        let adjustment = (value & 0x1ff) as i16;
+        Some(ReplayGain {
+            originator: ((value >> 10) & 0x7) as u8,
+            adjustment: if value & 0x200 != 0 {
+                -adjustment
+            } else {
+                adjustment
+            },
+        })
+    }
+
+    /// Gain adjustment in dB.
+    pub fn db(&self) -> f32 {
+        self.adjustment as f32 / 10.
+    }
+}
+
+impl MP3Metadata {
+    /// Returns the encoder delay and padding, read from the LAME tag or from the
+    /// "iTunSMPB" comment written by iTunes.
+    pub fn gapless_info(&self) -> Option<GaplessInfo> {
+        if let Some(lame) = self.lame_tag() {
+            return Some(GaplessInfo {
+                delay: lame.encoder_delay as u32,
+                padding: lame.padding as u32,
+                source: GaplessSource::Lame,
+            });
+        }
+        self.optional_info
+            .iter()
+            .flat_map(|info| info.comments.iter())
+            .filter(|comment| comment.description == "iTunSMPB")
+            .find_map(|comment| {
+                // " 00000000 00000210 000003C4 0000000000A5A6FC ...": the delay, the
+                // padding and the original number of samples, in hexadecimal.
+                let mut values = comment.text.split_whitespace().skip(1);
+                let delay = u32::from_str_radix(values.next()?, 16).ok()?;
+                let padding = u32::from_str_radix(values.next()?, 16).ok()?;
+                Some(GaplessInfo {
+                    delay,
+                    padding,
+                    source: GaplessSource::ITunSMPB,
+                })
+            })
+    }
+
+    /// Number of samples of actual audio: `total_samples` without the encoder delay and
+    /// padding nor the frame carrying the VBR header.
+    pub fn gapless_samples(&self) -> Option<u64> {
+        let info = self.gapless_info()?;
+        let header_samples = self
+            .vbr_header
+            .as_ref()
+            .map(|header| header.samples_per_frame as u64)
+            .unwrap_or(0);
+        Some(
+            self.total_samples
+                .saturating_sub(header_samples)
+                .saturating_sub(info.delay as u64)
+                .saturating_sub(info.padding as u64),
+        )
+    }
+
+    /// Duration of the actual audio, computed from [`MP3Metadata::gapless_samples`]
+    /// and the sampling frequency of the first segment, or of the VBR header.
+    pub fn gapless_duration(&self) -> Option<Duration> {
+        let sampling_freq = match self.segments.first() {
+            Some(segment) => segment.sampling_freq,
+            None => self.vbr_header.as_ref()?.sampling_freq,
+        } as u64;
+        if sampling_freq == 0 {
+            return None;
+        }
+        let nanos = self.gapless_samples()? * 1_000_000_000 / sampling_freq;
+        Some(Duration::new(
+            nanos / 1_000_000_000,
+            (nanos % 1_000_000_000) as u32,
+        ))
+    }
+
+    /// Builds a seek index from the frames, or from the VBR header or the ID3v2 "MLLT"
+    /// frame when no frame was collected. These come first when the frames only cover
+    /// the start of the stream (see [`MP3Metadata::partial`]).
+    pub fn seek_index(&self) -> Option<SeekIndex> {
+        let frames = SeekIndex::from_frames(&self.frames);
+        if self.partial {
+            self.table_seek_index()
+                .or_else(|| frames.map(SeekIndex::partial))
+        } else {
+            frames.or_else(|| self.table_seek_index())
+        }
+    }
+
+    /// Seek index covering the whole stream, from the VBR header or the "MLLT" frame.
+    fn table_seek_index(&self) -> Option<SeekIndex> {
+        self.vbr_header
+            .as_ref()
+            .and_then(SeekIndex::from_vbr_header)
+            .or_else(|| {
+                let start = self
+                    .segments
+                    .first()
+                    .map(|segment| segment.bytes.start as u64)
+                    .unwrap_or(0);
+                self.optional_info
+                    .iter()
+                    .filter_map(|info| info.mpeg_location_lookup_table.as_ref())
+                    .find_map(|table| SeekIndex::from_mllt(table, start))
+            })
+    }
+
+    /// Whether the stream parameters change along the stream, which usually means that
+    /// files were concatenated or that garbage was taken for frames.
+    pub fn has_parameter_changes(&self) -> bool {
+        self.segments.len() > 1
+    }
+
+    /// Returns the LAME extension of the Xing header, if any.
+    pub fn lame_tag(&self) -> Option<&LameTag> {
+        match self.vbr_header.as_ref()?.kind {
+            VbrHeaderKind::Xing(ref xing) => xing.lame.as_ref(),
+            VbrHeaderKind::Vbri(_) => None,
+        }
+    }
+
+    /// Locates the ancillary data following the main data of each frame. Returns `None`
+    /// unless frames were collected and every frame is a Layer III frame whose side
+    /// information was decoded (see [`ParseOptions::side_info`](crate::ParseOptions::side_info)).
+    pub fn ancillary_data(&self) -> Option<Vec<AncillaryData>> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        read_ancillary_data(&self.frames)
+    }
+
+    /// Locates the main data of each frame in the Layer III bit reservoir. Returns `None`
+    /// unless frames were collected and every frame is a Layer III frame whose side
+    /// information was decoded (see [`ParseOptions::side_info`](crate::ParseOptions::side_info)).
+    pub fn bit_reservoir(&self) -> Option<Vec<ReservoirDependency>> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let mut dependencies = Vec::with_capacity(self.frames.len());
+        for (index, frame) in self.frames.iter().enumerate() {
+            let side_info = frame.side_info.as_ref()?;
+            // Walk back through the main data areas of the previous frames until enough
+            // bytes have been found.
+            let mut remaining = side_info.main_data_begin as u32;
+            let mut first = index;
+            while remaining > 0 && first > 0 {
+                let previous = &self.frames[first - 1];
+                if previous.offset + previous.size != self.frames[first].offset {
+                    // Frames aren't contiguous, the reservoir is lost.
+                    break;
+                }
+                first -= 1;
+                remaining = remaining.saturating_sub(get_main_data_size(previous));
+            }
+            dependencies.push(ReservoirDependency {
+                depends_on: first..index,
+                independent: side_info.main_data_begin == 0,
+                before_start: remaining > 0,
+            });
+        }
+        Some(dependencies)
+    }
+}
+
+/// Run of consecutive frames with the same stream parameters.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Segment {
+    pub version: Version,
+    pub layer: Layer,
+    pub sampling_freq: u16,
+    pub chan_type: ChannelType,
+    /// Indexes in [`MP3Metadata::frames`].
+    pub frames: Range<usize>,
+    /// Byte offsets in the file, from the first header to the end of the last frame.
+    pub bytes: Range<u32>,
+}
+
+impl Segment {
+    pub(crate) fn new(frame: &Frame, index: usize) -> Segment {
+        Segment {
+            version: frame.version,
+            layer: frame.layer,
+            sampling_freq: frame.sampling_freq,
+            chan_type: frame.chan_type,
+            frames: index..index + 1,
+            bytes: frame.offset..frame.offset + frame.size,
+        }
+    }
+
+    /// Extends the segment with `frame` if it has the same parameters.
+    pub(crate) fn push(&mut self, frame: &Frame) -> bool {
+        if frame.version != self.version
+            || frame.layer != self.layer
+            || frame.sampling_freq != self.sampling_freq
+            || frame.chan_type != self.chan_type
+        {
+            return false;
+        }
+        self.frames.end += 1;
+        self.bytes.end = frame.offset + frame.size;
+        true
+    }
+}
+
+/// Bitrate statistics of the audio frames, the frame carrying a VBR header excluded.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AudioSummary {
+    /// Number of frames taken into account.
+    pub frames: u32,
+    /// Lowest frame bitrate, in kb/s.
+    pub min_bitrate: u16,
+    /// Highest frame bitrate, in kb/s.
+    pub max_bitrate: u16,
+    /// Number of frames for each bitrate index of the header (0 is free format).
+    pub bitrate_histogram: [u32; 16],
+    /// Sum of the frame sizes, in bytes.
+    pub bytes: u64,
+    /// Sum of the frame durations.
+    pub duration: Duration,
+    /// Sum of the frame bitrates, in kb/s.
+    pub bitrate_sum: u64,
+    pub bitrate_mode: BitrateMode,
+}
+
+impl AudioSummary {
+    pub(crate) fn add_frame(&mut self, frame: &Frame, bitrate_index: usize) {
+        if self.frames == 0 || frame.bitrate < self.min_bitrate {
+            self.min_bitrate = frame.bitrate;
+        }
+        if frame.bitrate > self.max_bitrate {
+            self.max_bitrate = frame.bitrate;
+        }
+        self.frames += 1;
+        self.bitrate_histogram[bitrate_index] += 1;
+        self.bytes += frame.size as u64;
+        if let Some(duration) = frame.duration {
+            self.duration += duration;
+        }
+        self.bitrate_sum += frame.bitrate as u64;
+    }
+
+    /// Classifies the stream using the LAME tag or the VBR header if any, the frame
+    /// bitrates otherwise.
+    pub(crate) fn classify(&mut self, vbr_header: Option<&VbrHeader>) {
+        let lame = vbr_header.and_then(|header| match header.kind {
+            VbrHeaderKind::Xing(ref xing) => xing.lame.as_ref(),
+            VbrHeaderKind::Vbri(_) => None,
+        });
+        self.bitrate_mode = match (lame.map(|lame| lame.vbr_method), vbr_header) {
+            (Some(LameVbrMethod::Cbr), _) | (Some(LameVbrMethod::Cbr2Pass), _) => BitrateMode::Cbr,
+            (Some(LameVbrMethod::Abr), _) | (Some(LameVbrMethod::Abr2Pass), _) => BitrateMode::Abr,
+            (Some(LameVbrMethod::VbrOld), _)
+            | (Some(LameVbrMethod::VbrMtrh), _)
+            | (Some(LameVbrMethod::VbrMt), _)
+            | (Some(LameVbrMethod::VbrNew), _) => BitrateMode::Vbr,
+            (_, Some(header)) => match header.kind {
+                VbrHeaderKind::Xing(ref xing) if xing.info => BitrateMode::Cbr,
+                _ => BitrateMode::Vbr,
+            },
+            _ if self.frames == 0 => BitrateMode::Unknown,
+            _ if self.min_bitrate == self.max_bitrate => BitrateMode::Cbr,
+            _ => BitrateMode::Vbr,
+        };
+    }
+
+    /// Arithmetic mean of the frame bitrates, in kb/s.
+    pub fn mean_bitrate(&self) -> Option<f64> {
+        if self.frames == 0 {
+            None
+        } else {
+            Some(self.bitrate_sum as f64 / self.frames as f64)
+        }
+    }
+
+    /// Average bitrate over the duration of the stream, in kb/s.
+    pub fn average_bitrate(&self) -> Option<f64> {
+        let seconds = self.duration.as_secs_f64();
+        if seconds > 0. {
+            Some(self.bytes as f64 * 8. / seconds / 1000.)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the main data of a Layer III frame is stored, see
+/// [`MP3Metadata::bit_reservoir`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReservoirDependency {
+    /// Indexes in [`MP3Metadata::frames`] of the previous frames holding part of the main
+    /// data of this frame.
+    pub depends_on: Range<usize>,
+    /// Whether the main data starts in this frame, so that the stream can be cut right
+    /// before it without glitches.
+    pub independent: bool,
+    /// Whether the main data starts before the first frame (or before a gap in the
+    /// stream), so that the frame can't be fully decoded.
+    pub before_start: bool,
 }
 
 #[derive(Debug, Default, Eq, PartialEq)]
@@ -38,12 +577,109 @@ pub struct AudioTag {
     pub album: String,
     pub year: u16,
     pub comment: String,
+    /// Track number, only available in ID3v1.1 tags.
+    pub track: Option<u8>,
     pub genre: Genre,
+    /// Speed from the extended "TAG+" block: 1 for slow, 2 for medium, 3 for fast and 4
+    /// for hardcore.
+    pub speed: Option<u8>,
+    /// Free-text genre from the extended "TAG+" block.
+    pub extended_genre: Option<String>,
+    /// Start of the music from the extended "TAG+" block, as "mmm:ss".
+    pub start_time: Option<String>,
+    /// End of the music from the extended "TAG+" block, as "mmm:ss".
+    pub end_time: Option<String>,
 }
 
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct Url(pub String);
 
+/// Encoder delay and padding, needed for gapless playback.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GaplessInfo {
+    /// Number of samples added by the encoder at the start of the stream.
+    pub delay: u32,
+    /// Number of samples added by the encoder at the end of the stream.
+    pub padding: u32,
+    pub source: GaplessSource,
+}
+
+/// MPEG location lookup table ("MLLT" frame) of an ID3v2 tag.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MpegLocationLookupTable {
+    pub frames_between_reference: u16,
+    pub bytes_between_reference: u32,
+    pub millis_between_reference: u32,
+    pub references: Vec<MlltReference>,
+}
+
+/// Reference of an [`MpegLocationLookupTable`]: how far it is from the previous one,
+/// on top of the distance between references.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MlltReference {
+    pub bytes_deviation: u32,
+    pub millis_deviation: u32,
+}
+
+/// Comment ("COMM" frame) of an ID3v2 tag.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Comment {
+    /// ISO-639-2 language code.
+    pub language: String,
+    pub description: String,
+    pub text: String,
+}
+
+/// APEv1 or APEv2 tag.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct ApeTag {
+    /// 1000 for APEv1, 2000 for APEv2.
+    pub version: u32,
+    pub read_only: bool,
+    pub items: Vec<ApeItem>,
+}
+
+impl ApeTag {
+    /// Returns the item with the given key. Keys are case insensitive.
+    pub fn get(&self, key: &str) -> Option<&ApeItem> {
+        self.items
+            .iter()
+            .find(|item| item.key.eq_ignore_ascii_case(key))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ApeItem {
+    pub key: String,
+    pub value: ApeItemValue,
+    pub read_only: bool,
+}
+
+/// Lyrics3 tag, stored right before the ID3v1 tag.
+///
+/// Lyrics3 v1 tags only contain the lyrics.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Lyrics3Tag {
+    pub version: Lyrics3Version,
+    /// Lyrics, possibly with "[mm:ss]" time stamps ("LYR" field).
+    pub lyrics: Option<String>,
+    /// Additional information ("INF" field).
+    pub additional_info: Option<String>,
+    /// Lyrics author name ("AUT" field).
+    pub author: Option<String>,
+    /// Extended album name ("EAL" field).
+    pub album: Option<String>,
+    /// Extended artist name ("EAR" field).
+    pub artist: Option<String>,
+    /// Extended track title ("ETT" field).
+    pub title: Option<String>,
+    /// Links to image files ("IMG" field).
+    pub images: Option<String>,
+    /// Indications ("IND" field): whether there are lyrics, whether they contain time
+    /// stamps and whether the track shouldn't be picked by random selection.
+    pub indications: Option<String>,
+}
+
 // TODO: Add picture support
 /// id3.org/id3v2.3.0#Declared_ID3v2_frames#Text_information_frames_-_details
 #[derive(Debug, Default, Eq, PartialEq)]
@@ -309,6 +945,16 @@ pub struct OptionalAudioTags {
     /// string with the involvement directly followed by a terminated string with
     /// the involvee followed by a new involvement and so on.
     pub involved_people: Option<String>,
+    /// The 'Comments' frame is intended for any kind of full text information that
+    /// does not fit in any other frame. It consists of a frame header followed by
+    /// encoding, language and content descriptors and is ended with the actual comment
+    /// as a text string.
+    pub comments: Vec<Comment>,
+    /// To increase performance and accuracy of jumps within a MPEG audio file, frames
+    /// with timecodes in different locations in the file might be useful. The 'MPEG
+    /// location lookup table' frame includes references that the software can use to
+    /// calculate positions in the file.
+    pub mpeg_location_lookup_table: Option<MpegLocationLookupTable>,
 
     /// The 'Commercial information' frame is a URL pointing at a webpage with
     /// information such as where the album can be bought. There may be more than
@@ -337,3 +983,104 @@ pub struct OptionalAudioTags {
     /// wepage for the publisher.
     pub publishers_official_webpage: Option<Url>,
 }
+
+/// Problem found by a lenient parse, see
+/// [`read_from_file_lenient`](crate::read_from_file_lenient).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Offset in the file where the problem was found.
+    pub offset: u64,
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn from_error(error: &Error, offset: u64) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            offset: error.offset.unwrap_or(offset),
+            kind: error.kind,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Error returned when a file can't be parsed.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    offset: Option<u64>,
+    context: Option<&'static str>,
+    source: Option<io::Error>,
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            offset: None,
+            context: None,
+            source: None,
+        }
+    }
+
+    pub(crate) fn with_offset(mut self, offset: u64) -> Error {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub(crate) fn with_context(mut self, context: &'static str) -> Error {
+        self.context = Some(context);
+        self
+    }
+
+    pub(crate) fn with_source(mut self, source: io::Error) -> Error {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Offset in the file of the data being read.
+    pub fn offset(&self) -> Option<u64> {
+        self.offset
+    }
+
+    /// What was being read: a kind of tag or a frame.
+    pub fn context(&self) -> Option<&'static str> {
+        self.context
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Error {
+        Error::new(kind)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.kind.fmt(f)?;
+        if let Some(context) = self.context {
+            write!(f, " while reading the {}", context)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " at offset {}", offset)?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, ": {}", source)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source as &(dyn error::Error + 'static))
+    }
+}
@@ -1,8 +1,8 @@
 use std::time::Duration;
 
 use crate::consts::SAMPLES_PER_FRAME;
-use crate::enums::{Layer, Version};
-use crate::types::Url;
+use crate::enums::{ChannelType, Codepage, Layer, Version, CRC};
+use crate::types::{Comment, Frame, MlltReference, MpegLocationLookupTable, Url};
 
 pub fn compute_duration(v: Version, l: Layer, sample_rate: u16) -> Option<Duration> {
     if sample_rate == 0 {
@@ -22,6 +22,80 @@ pub fn compute_duration(v: Version, l: Layer, sample_rate: u16) -> Option<Durati
     ))
 }
 
+pub fn get_samples_per_frame(v: Version, l: Layer) -> u32 {
+    match v {
+        Version::MPEG1 => SAMPLES_PER_FRAME[0][get_layer_value(l)],
+        _ => SAMPLES_PER_FRAME[1][get_layer_value(l)],
+    }
+}
+
+/// Length in bytes of a slot: Layer I frames are made of 4-byte slots.
+pub fn get_slot_size(l: Layer) -> u32 {
+    match l {
+        Layer::Layer1 => 4,
+        _ => 1,
+    }
+}
+
+/// Number of slots per frame for a 1 b/s bitrate and a 1 Hz sampling frequency.
+fn get_slots_factor(v: Version, l: Layer) -> u64 {
+    match (v, l) {
+        (_, Layer::Layer1) => 12,
+        (Version::MPEG1, _) | (_, Layer::Layer2) => 144,
+        _ => 72,
+    }
+}
+
+/// Length in bytes of a frame, header included, as defined by ISO/IEC 11172-3 and
+/// 13818-3. `bitrate` is in b/s.
+pub fn get_frame_size(
+    v: Version,
+    l: Layer,
+    bitrate: u32,
+    sampling_freq: u16,
+    padding: bool,
+) -> u32 {
+    if sampling_freq == 0 {
+        return 0;
+    }
+    let slots = get_slots_factor(v, l) * bitrate as u64 / sampling_freq as u64;
+    (slots as u32 + padding as u32) * get_slot_size(l)
+}
+
+/// Bitrate in b/s of a frame of `size` bytes, padding excluded.
+pub fn get_bitrate(v: Version, l: Layer, size: u32, sampling_freq: u16) -> u32 {
+    let slots = (size / get_slot_size(l)) as u64;
+    (slots * sampling_freq as u64 / get_slots_factor(v, l)) as u32
+}
+
+/// Number of frames, rounded to the nearest, held by `bytes` bytes of a constant bitrate
+/// stream. `bitrate` is in b/s.
+pub fn get_frame_count(v: Version, l: Layer, bitrate: u32, sampling_freq: u16, bytes: u64) -> u64 {
+    // Mean length of a frame, multiplied by the sampling frequency.
+    let scaled_size = get_slots_factor(v, l) * bitrate as u64 * get_slot_size(l) as u64;
+    if scaled_size == 0 {
+        return 0;
+    }
+    (bytes * sampling_freq as u64 * 2 + scaled_size) / (2 * scaled_size)
+}
+
+/// Size in bytes of the Layer III side information.
+pub fn get_side_info_size(v: Version, c: ChannelType) -> usize {
+    match (v, c) {
+        (Version::MPEG1, ChannelType::SingleChannel) => 17,
+        (Version::MPEG1, _) => 32,
+        (_, ChannelType::SingleChannel) => 9,
+        _ => 17,
+    }
+}
+
+/// Size in bytes of the part of a Layer III frame available for main data.
+pub fn get_main_data_size(frame: &Frame) -> u32 {
+    let crc = if frame.crc == CRC::Added { 2 } else { 0 };
+    let overhead = 4 + crc + get_side_info_size(frame.version, frame.chan_type) as u32;
+    frame.size.saturating_sub(overhead)
+}
+
 pub fn get_line(v: Version, l: Layer) -> usize {
     match (v, l) {
         (Version::MPEG1, Layer::Layer1) => 0,
@@ -57,7 +131,20 @@ pub fn create_latin1_str(buf: &[u8]) -> String {
     String::from_utf16_lossy(utf16.as_ref())
 }
 
-pub fn create_utf16_str(buf: &[u8]) -> String {
+pub fn create_id3v1_str(buf: &[u8], codepage: Codepage) -> String {
+    // Fields are padded with NUL bytes (or spaces for some taggers); whatever follows
+    // the first NUL is garbage.
+    let buf = match buf.iter().position(|c| *c == 0) {
+        Some(end) => &buf[..end],
+        None => buf,
+    };
+    let s = buf.iter().map(|c| codepage.decode(*c)).collect::<String>();
+    s.trim_end_matches(' ').to_owned()
+}
+
+/// Code units of UTF-16 text, big-endian after a "\u{feff}" BOM, little-endian
+/// otherwise.
+fn utf16_units(buf: &[u8]) -> Vec<u16> {
     let mut v = Vec::<u16>::new();
     if buf.len() >= 2 {
         // BOM: \u{feff}
@@ -67,14 +154,14 @@ pub fn create_utf16_str(buf: &[u8]) -> String {
             for i in 1..buf.len() / 2 {
                 v.push(((buf[2 * i] as u16) << 8) | (buf[2 * i + 1] as u16));
             }
-            return String::from_utf16_lossy(v.as_ref());
+            return v;
         } else if buf[0] == 0xff && buf[1] == 0xfe {
             // UTF-16LE
             v.reserve(buf.len() / 2 - 1);
             for i in 1..buf.len() / 2 {
                 v.push(((buf[2 * i + 1] as u16) << 8) | (buf[2 * i] as u16));
             }
-            return String::from_utf16_lossy(v.as_ref());
+            return v;
         }
     }
     // try as UTF-16LE
@@ -82,7 +169,21 @@ pub fn create_utf16_str(buf: &[u8]) -> String {
     for i in 0..buf.len() / 2 {
         v.push(((buf[2 * i + 1] as u16) << 8) | (buf[2 * i] as u16))
     }
-    String::from_utf16_lossy(v.as_ref())
+    v
+}
+
+/// Decodes UTF-16 code units, also returning whether some had to be replaced.
+fn decode_utf16(units: &[u16]) -> (String, bool) {
+    let mut lossy = false;
+    let s = std::char::decode_utf16(units.iter().cloned())
+        .map(|c| {
+            c.unwrap_or_else(|_| {
+                lossy = true;
+                std::char::REPLACEMENT_CHARACTER
+            })
+        })
+        .collect();
+    (s, lossy)
 }
 
 pub fn create_utf8_str(mut buf: &[u8]) -> String {
@@ -124,48 +225,132 @@ pub fn get_url_fields(buf: &[u8], pos: usize, size: u32, changes: &mut bool, val
     }
 }
 
-pub fn get_field(buf: &[u8], pos: usize, size: u32) -> String {
+/// Decodes the text field of `size` bytes at `pos`, also returning whether characters
+/// were lost.
+pub fn get_field(buf: &[u8], pos: usize, size: u32) -> (String, bool) {
     let buf = &buf[pos..][..size as usize];
     if buf.is_empty() {
-        String::new()
-    } else if buf[0] == 0 {
+        (String::new(), false)
+    } else {
+        decode_text(&buf[1..], buf[0])
+    }
+}
+
+/// Splits `buf` after the first NUL character of the given encoding.
+fn split_terminated(buf: &[u8], encoding: u8) -> (&[u8], &[u8]) {
+    let end = if encoding == 1 || encoding == 2 {
+        (0..buf.len() / 2)
+            .map(|i| i * 2)
+            .find(|i| buf[*i] == 0 && buf[*i + 1] == 0)
+            .map(|i| (i, i + 2))
+    } else {
+        buf.iter().position(|c| *c == 0).map(|i| (i, i + 1))
+    };
+    match end {
+        Some((end, next)) => (&buf[..end], &buf[next..]),
+        None => (buf, &[]),
+    }
+}
+
+/// Decodes `buf` in the given ID3v2 encoding, also returning whether characters were
+/// lost.
+fn decode_text(buf: &[u8], encoding: u8) -> (String, bool) {
+    match encoding {
         // ISO-8859-1
-        create_latin1_str(&buf[1..])
-    } else if buf[0] == 1 {
+        0 => (create_latin1_str(buf), false),
         // UTF-16, requires a BOM
-        create_utf16_str(&buf[1..])
-    } else if buf[0] == 3 {
-        // UTF-8
-        create_utf8_str(&buf[1..])
-    } else {
-        String::new()
+        1 => decode_utf16(&utf16_units(buf)),
+        2 => {
+            // UTF-16BE without BOM
+            let v = buf
+                .chunks_exact(2)
+                .map(|c| ((c[0] as u16) << 8) | c[1] as u16)
+                .collect::<Vec<u16>>();
+            decode_utf16(&v)
+        }
+        3 => {
+            // Invalid UTF-8 gives an empty string.
+            let s = create_utf8_str(buf);
+            let lossy = s.is_empty() && buf.iter().any(|c| *c != 0);
+            (s, lossy)
+        }
+        _ => (String::new(), true),
+    }
+}
+
+pub fn get_comment(buf: &[u8], pos: usize, size: u32) -> Option<Comment> {
+    let buf = &buf[pos..][..size as usize];
+    if buf.len() < 4 {
+        return None;
     }
+    let encoding = buf[0];
+    let (description, text) = split_terminated(&buf[4..], encoding);
+    let (text, _) = split_terminated(text, encoding);
+    Some(Comment {
+        language: create_latin1_str(&buf[1..4]),
+        description: decode_text(description, encoding).0,
+        text: decode_text(text, encoding).0,
+    })
 }
 
+pub fn get_mllt(buf: &[u8], pos: usize, size: u32) -> Option<MpegLocationLookupTable> {
+    let buf = &buf[pos..][..size as usize];
+    if buf.len() < 10 {
+        return None;
+    }
+    let bytes_bits = buf[8] as u32;
+    let millis_bits = buf[9] as u32;
+    if bytes_bits + millis_bits == 0 || bytes_bits > 32 || millis_bits > 32 {
+        return None;
+    }
+    let mut reader = BitReader::new(&buf[10..]);
+    let mut references = Vec::new();
+    while let (Some(bytes_deviation), Some(millis_deviation)) =
+        (reader.read(bytes_bits), reader.read(millis_bits))
+    {
+        references.push(MlltReference {
+            bytes_deviation,
+            millis_deviation,
+        });
+    }
+    Some(MpegLocationLookupTable {
+        frames_between_reference: u16::from_be_bytes([buf[0], buf[1]]),
+        bytes_between_reference: u32::from_be_bytes([0, buf[2], buf[3], buf[4]]),
+        millis_between_reference: u32::from_be_bytes([0, buf[5], buf[6], buf[7]]),
+        references,
+    })
+}
+
+/// Reads a text field unless `value` is already set. Returns whether characters were
+/// lost.
 pub fn get_text_field(
     buf: &[u8],
     pos: usize,
     size: u32,
     changes: &mut bool,
     value: &mut Option<String>,
-) {
+) -> bool {
     if value.is_some() || size < 2 {
-        return;
+        return false;
     }
     if !(*changes) {
         *changes = true;
     }
-    *value = Some(get_field(buf, pos, size));
+    let (tmp, lossy) = get_field(buf, pos, size);
+    *value = Some(tmp);
+    lossy
 }
 
+/// Reads a text field holding values separated by '/'. Returns whether characters
+/// were lost.
 pub fn get_text_fields(
     buf: &[u8],
     pos: usize,
     size: u32,
     changes: &mut bool,
     value: &mut Vec<String>,
-) {
-    let tmp = get_field(buf, pos, size);
+) -> bool {
+    let (tmp, lossy) = get_field(buf, pos, size);
     let tmp_v = tmp.split('/');
     for entry in tmp_v {
         if !entry.is_empty() {
@@ -175,4 +360,37 @@ pub fn get_text_fields(
     if !(*changes) {
         *changes = true;
     }
+    lossy
+}
+
+/// Reads big-endian bit fields out of a byte slice.
+pub struct BitReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(buf: &'a [u8]) -> BitReader<'a> {
+        BitReader { buf, pos: 0 }
+    }
+
+    /// Number of bits read so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Reads the next `bits` (at most 32) bits, or returns `None` at the end of the
+    /// buffer.
+    pub fn read(&mut self, bits: u32) -> Option<u32> {
+        if self.pos + bits as usize > self.buf.len() * 8 {
+            return None;
+        }
+        let mut value = 0u32;
+        for _ in 0..bits {
+            let bit = (self.buf[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | bit as u32;
+            self.pos += 1;
+        }
+        Some(value)
+    }
 }
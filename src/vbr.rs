@@ -0,0 +1,191 @@
+use crate::crc::crc16_lame;
+use crate::enums::{LameVbrMethod, VbrHeaderKind, CRC};
+use crate::types::{Frame, LameTag, ReplayGain, VbrHeader, VbriHeader, XingHeader};
+use crate::utils::{create_latin1_str, get_samples_per_frame, get_side_info_size};
+
+const FRAMES_FLAG: u32 = 0x1;
+const BYTES_FLAG: u32 = 0x2;
+const TOC_FLAG: u32 = 0x4;
+const QUALITY_FLAG: u32 = 0x8;
+
+const LAME_TAG_SIZE: usize = 36;
+
+const VBRI_OFFSET: usize = 4 + 32;
+
+fn read_u32(buf: &[u8]) -> u32 {
+    u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]])
+}
+
+/// Looks for a VBR header in `buf`, which contains the whole first frame.
+pub fn read_vbr_header(buf: &[u8], frame: &Frame) -> Option<VbrHeader> {
+    let kind = match read_xing(buf, frame) {
+        Some(xing) => VbrHeaderKind::Xing(xing),
+        None => VbrHeaderKind::Vbri(read_vbri(buf)?),
+    };
+    Some(VbrHeader {
+        offset: frame.offset,
+        sampling_freq: frame.sampling_freq,
+        samples_per_frame: get_samples_per_frame(frame.version, frame.layer),
+        kind,
+    })
+}
+
+fn read_xing(buf: &[u8], frame: &Frame) -> Option<XingHeader> {
+    // The Xing header is written right after the side information. Some encoders
+    // also take the CRC into account.
+    let pos = 4 + get_side_info_size(frame.version, frame.chan_type);
+    let mut positions = vec![pos];
+    if frame.crc == CRC::Added {
+        positions.push(pos + 2);
+    }
+    positions.into_iter().find_map(|pos| read_xing_at(buf, pos))
+}
+
+fn read_xing_at(buf: &[u8], mut pos: usize) -> Option<XingHeader> {
+    let info = match buf.get(pos..pos + 4)? {
+        b"Xing" => false,
+        b"Info" => true,
+        _ => return None,
+    };
+    let flags = read_u32(buf.get(pos + 4..pos + 8)?);
+    pos += 8;
+    let mut header = XingHeader {
+        info,
+        frames: None,
+        bytes: None,
+        toc: None,
+        quality: None,
+        lame: None,
+    };
+    if flags & FRAMES_FLAG != 0 {
+        header.frames = Some(read_u32(buf.get(pos..pos + 4)?));
+        pos += 4;
+    }
+    if flags & BYTES_FLAG != 0 {
+        header.bytes = Some(read_u32(buf.get(pos..pos + 4)?));
+        pos += 4;
+    }
+    if flags & TOC_FLAG != 0 {
+        let mut toc = [0; 100];
+        toc.copy_from_slice(buf.get(pos..pos + 100)?);
+        header.toc = Some(toc);
+        pos += 100;
+    }
+    if flags & QUALITY_FLAG != 0 {
+        header.quality = Some(read_u32(buf.get(pos..pos + 4)?));
+        pos += 4;
+    }
+    header.lame = read_lame(buf, pos);
+    Some(header)
+}
+
+fn read_vbri(buf: &[u8]) -> Option<VbriHeader> {
+    // The VBRI header is always 32 bytes after the frame header.
+    let buf = buf.get(VBRI_OFFSET..)?;
+    if buf.len() < 26 || &buf[..4] != b"VBRI" {
+        return None;
+    }
+    let read_u16 = |pos: usize| u16::from_be_bytes([buf[pos], buf[pos + 1]]);
+    let entries = read_u16(18) as usize;
+    let scale = read_u16(20) as u32;
+    let entry_size = read_u16(22) as usize;
+    if entry_size == 0 || entry_size > 4 {
+        return None;
+    }
+    let toc = buf
+        .get(26..26 + entries * entry_size)?
+        .chunks(entry_size)
+        .map(|entry| entry.iter().fold(0, |acc, c| (acc << 8) | *c as u32) * scale)
+        .collect();
+    Some(VbriHeader {
+        version: read_u16(4),
+        delay: read_u16(6),
+        quality: read_u16(8),
+        bytes: read_u32(&buf[10..]),
+        frames: read_u32(&buf[14..]),
+        frames_per_entry: read_u16(24),
+        toc,
+    })
+}
+
+/// Reads the LAME extension starting at `pos` in the frame `buf`.
+fn read_lame(buf: &[u8], pos: usize) -> Option<LameTag> {
+    let tag = buf.get(pos..pos + LAME_TAG_SIZE)?;
+    // FFmpeg writes the same extension.
+    if !matches!(&tag[..4], b"LAME" | b"Lavf" | b"Lavc") {
+        return None;
+    }
+    let tag_crc = u16::from_be_bytes([tag[34], tag[35]]);
+    Some(LameTag {
+        encoder: create_latin1_str(&tag[..9])
+            .trim_end_matches(|c| c == '\0' || c == ' ')
+            .to_owned(),
+        revision: tag[9] >> 4,
+        vbr_method: LameVbrMethod::from(tag[9] & 0xf),
+        lowpass: tag[10] as u32 * 100,
+        peak_amplitude: read_u32(&tag[11..]),
+        track_gain: ReplayGain::read(u16::from_be_bytes([tag[15], tag[16]])),
+        album_gain: ReplayGain::read(u16::from_be_bytes([tag[17], tag[18]])),
+        encoding_flags: tag[19] >> 4,
+        ath_type: tag[19] & 0xf,
+        bitrate: tag[20],
+        encoder_delay: ((tag[21] as u16) << 4) | (tag[22] as u16 >> 4),
+        padding: (((tag[22] & 0xf) as u16) << 8) | tag[23] as u16,
+        noise_shaping: tag[24] & 0x3,
+        stereo_mode: (tag[24] >> 2) & 0x7,
+        unwise_settings: tag[24] & 0x20 != 0,
+        source_sample_rate: tag[24] >> 6,
+        mp3_gain: tag[25] as i8,
+        surround: (tag[26] >> 3) & 0x7,
+        preset: u16::from_be_bytes([tag[26], tag[27]]) & 0x7ff,
+        music_length: read_u32(&tag[28..]),
+        music_crc: u16::from_be_bytes([tag[32], tag[33]]),
+        tag_crc,
+        // The CRC covers the whole frame up to the CRC field.
+        crc_valid: crc16_lame(&buf[..pos + 34]) == tag_crc,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{ChannelType, Layer, Version};
+
+    fn frame(version: Version, chan_type: ChannelType) -> Frame {
+        Frame {
+            version,
+            layer: Layer::Layer3,
+            chan_type,
+            sampling_freq: 44100,
+            ..Frame::default()
+        }
+    }
+
+    fn xing_frame(pos: usize) -> Vec<u8> {
+        let mut buf = vec![0; 400];
+        buf[pos..pos + 4].copy_from_slice(b"Info");
+        buf[pos + 7] = (FRAMES_FLAG | QUALITY_FLAG) as u8;
+        buf[pos + 8..pos + 12].copy_from_slice(&1234u32.to_be_bytes());
+        buf[pos + 15] = 78;
+        buf
+    }
+
+    #[test]
+    fn xing_position() {
+        for (version, chan_type, pos) in [
+            (Version::MPEG1, ChannelType::Stereo, 36),
+            (Version::MPEG1, ChannelType::SingleChannel, 21),
+            (Version::MPEG2, ChannelType::JointStereo, 21),
+            (Version::MPEG2_5, ChannelType::SingleChannel, 13),
+        ] {
+            let frame = frame(version, chan_type);
+            let header = read_xing(&xing_frame(pos), &frame).expect("no Xing header");
+            assert!(header.info);
+            assert_eq!(header.frames, Some(1234));
+            assert_eq!(header.bytes, None);
+            assert_eq!(header.toc, None);
+            assert_eq!(header.quality, Some(78));
+            assert_eq!(read_xing(&xing_frame(pos + 1), &frame), None);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::ParseOptions;
+
+/// Writes `bits` bits of `value` at bit `pos` of `buf`.
+fn write_bits(buf: &mut [u8], pos: usize, bits: usize, value: u32) {
+    for i in 0..bits {
+        if value >> (bits - 1 - i) & 1 != 0 {
+            buf[(pos + i) / 8] |= 0x80 >> ((pos + i) % 8);
+        }
+    }
+}
+
+/// MPEG-1 Layer III, 128 kb/s, 44100 Hz, mono, without CRC: 396 bytes of main data
+/// after the 17 bytes of side information.
+fn frame(main_data_begin: u32, part2_3_length: u32) -> Vec<u8> {
+    let mut frame = vec![0; 417];
+    frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0xc0]);
+    write_bits(&mut frame[4..21], 0, 9, main_data_begin);
+    // Each granule starts with its part2_3_length, after main_data_begin, the private
+    // bits and scfsi.
+    write_bits(&mut frame[4..21], 18, 12, part2_3_length);
+    write_bits(&mut frame[4..21], 18 + 59, 12, part2_3_length);
+    frame
+}
+
+#[test]
+fn ranges() {
+    // 300 bytes of main data for the first two frames, the second one starting 50 bytes
+    // before its header.
+    let mut buf = [frame(0, 1200), frame(50, 1200), frame(0, 0)].concat();
+    buf[321..325].copy_from_slice(b"ANC!");
+    let options = ParseOptions::new().side_info(true);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice failed");
+    let ancillary = meta.ancillary_data().expect("no side info");
+    assert_eq!(ancillary.len(), 3);
+    assert_eq!(ancillary[0].ranges, vec![321..367]);
+    assert!(ancillary[0].starts_with(&buf, b"ANC!"));
+    assert_eq!(ancillary[0].bytes(&buf).len(), 46);
+    assert_eq!(ancillary[1].ranges, vec![688..834]);
+    assert!(!ancillary[1].starts_with(&buf, b"ANC!"));
+    // Nothing follows the last frame.
+    assert_eq!(ancillary[2].ranges, vec![855..1251]);
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.ancillary_data(), None);
+
+    // Without frames.
+    let options = ParseOptions::new().side_info(true).frames(false);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice failed");
+    assert_eq!(meta.ancillary_data(), None);
+}
+
+#[test]
+fn across_frames() {
+    // The main data of the second frame starts 300 bytes before its header, right after
+    // the 96 bytes of main data of the first frame, and is 50 bytes long: its ancillary
+    // data is the rest of the first frame and the whole second frame.
+    let buf = [frame(0, 48 * 8), frame(300, 25 * 8), frame(0, 0)].concat();
+    let options = ParseOptions::new().side_info(true);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice failed");
+    let ancillary = meta.ancillary_data().expect("no side info");
+    assert!(ancillary[0].is_empty());
+    assert_eq!(ancillary[0].overlap, 0);
+    assert_eq!(ancillary[1].ranges, vec![167..417, 438..834]);
+    assert_eq!(ancillary[1].len(), 250 + 396);
+}
+
+#[test]
+fn overlapping_main_data() {
+    // 192 bytes of main data for the first frame, while the second one claims that its
+    // main data starts after the first 96 bytes.
+    let buf = [frame(0, 96 * 8), frame(300, 25 * 8), frame(0, 0)].concat();
+    let options = ParseOptions::new().side_info(true);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice failed");
+    let ancillary = meta.ancillary_data().expect("no side info");
+    assert!(ancillary[0].is_empty());
+    assert_eq!(ancillary[0].overlap, 96);
+    assert_eq!(ancillary[1].overlap, 0);
+}
@@ -0,0 +1,97 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::ApeItemValue;
+
+fn ape_item(key: &str, flags: u32, value: &[u8]) -> Vec<u8> {
+    let mut item = Vec::new();
+    item.extend(&(value.len() as u32).to_le_bytes());
+    item.extend(&flags.to_le_bytes());
+    item.extend(key.as_bytes());
+    item.push(0);
+    item.extend(value);
+    item
+}
+
+fn ape_header(version: u32, size: usize, items: u32, flags: u32) -> Vec<u8> {
+    let mut header = b"APETAGEX".to_vec();
+    header.extend(&version.to_le_bytes());
+    header.extend(&(size as u32).to_le_bytes());
+    header.extend(&items.to_le_bytes());
+    header.extend(&flags.to_le_bytes());
+    header.extend(&[0; 8]);
+    header
+}
+
+#[test]
+fn apev2() {
+    let mut buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let frames = mp3_metadata::read_from_slice(&buf)
+        .expect("read_from_slice failed")
+        .frames
+        .len();
+    let id3v1 = buf.split_off(buf.len() - 128);
+
+    let mut items = ape_item("Title", 0, b"Some title");
+    items.extend(ape_item(
+        "Cover Art (Front)",
+        1 << 1,
+        &[0xff, 0xd8, 0xff, 0],
+    ));
+    items.extend(ape_item("Related", (2 << 1) | 1, b"http://example.com"));
+    let size = items.len() + 32;
+    buf.extend(ape_header(2000, size, 3, (1 << 31) | (1 << 29)));
+    buf.extend(items);
+    buf.extend(ape_header(2000, size, 3, 1 << 31));
+    buf.extend(id3v1);
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), frames);
+    assert!(meta.tag.is_some());
+    let ape = meta.ape.expect("no APE tag");
+    assert_eq!(ape.version, 2000);
+    assert!(!ape.read_only);
+    assert_eq!(ape.items.len(), 3);
+    assert_eq!(
+        ape.get("TITLE").map(|item| &item.value),
+        Some(&ApeItemValue::Text("Some title".to_owned()))
+    );
+    assert_eq!(
+        ape.items[1].value,
+        ApeItemValue::Binary(vec![0xff, 0xd8, 0xff, 0])
+    );
+    assert_eq!(
+        ape.items[2].value,
+        ApeItemValue::Locator("http://example.com".to_owned())
+    );
+    assert!(ape.items[2].read_only);
+}
+
+#[test]
+fn apev1() {
+    let mut buf = std::fs::read("assets/test.mp3").expect("read failed");
+    buf.truncate(buf.len() - 128);
+    let frames = mp3_metadata::read_from_slice(&buf)
+        .expect("read_from_slice failed")
+        .frames
+        .len();
+
+    // APEv1 has no header and no item flags.
+    let items = ape_item("Artist", 0xffff_ffff, b"Me");
+    let size = items.len() + 32;
+    buf.extend(items);
+    buf.extend(ape_header(1000, size, 1, 0));
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), frames);
+    assert_eq!(meta.tag, None);
+    let ape = meta.ape.expect("no APE tag");
+    assert_eq!(ape.version, 1000);
+    assert_eq!(
+        ape.items,
+        vec![mp3_metadata::ApeItem {
+            key: "Artist".to_owned(),
+            value: ApeItemValue::Text("Me".to_owned()),
+            read_only: false,
+        }]
+    );
+}
@@ -73,8 +73,7 @@ fn basic() {
             mp3_metadata::ChannelType::SingleChannel,
             "channel type"
         );
-        assert!(!frame.intensity_stereo, "intensity stereo");
-        assert!(!frame.ms_stereo, "ms stereo");
+        assert_eq!(frame.mode_extension, None, "mode extension");
         assert_eq!(frame.copyright, mp3_metadata::Copyright::None, "copyright");
         assert_eq!(frame.status, mp3_metadata::Status::Copy, "status");
         assert_eq!(frame.emphasis, mp3_metadata::Emphasis::None, "emphasis");
@@ -84,12 +83,17 @@ fn basic() {
     assert_eq!(
         meta.tag,
         Some(mp3_metadata::AudioTag {
-            title: "Test of MP3 File              ".to_owned(),
-            artist: "Me                            ".to_owned(),
-            album: "Me                            ".to_owned(),
+            title: "Test of MP3 File".to_owned(),
+            artist: "Me".to_owned(),
+            album: "Me".to_owned(),
             year: 2006,
-            comment: "test                        ".to_owned(),
+            comment: "test".to_owned(),
+            track: Some(1),
             genre: mp3_metadata::Genre::Other,
+            speed: None,
+            extended_genre: None,
+            start_time: None,
+            end_time: None,
         }),
         "tag"
     );
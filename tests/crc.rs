@@ -0,0 +1,39 @@
+extern crate mp3_metadata;
+
+#[test]
+fn valid() {
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    assert_eq!(meta.crc_failures, 0);
+    assert!(meta
+        .frames
+        .iter()
+        .all(|frame| frame.crc == mp3_metadata::CRC::Added && frame.crc_valid == Some(true)));
+    assert!(meta.frames[0].crc_checksum.is_some());
+}
+
+#[test]
+fn corrupted() {
+    let mut buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    // Flip a bit of the side information of the fourth frame.
+    let offset = meta.frames[3].offset as usize;
+    buf[offset + 8] ^= 0x10;
+    // And a bit of the main data of the fifth one, which isn't protected.
+    let offset = meta.frames[4].offset as usize;
+    buf[offset + 100] ^= 0x10;
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.crc_failures, 1);
+    assert_eq!(meta.frames[3].crc_valid, Some(false));
+    assert_eq!(meta.frames[4].crc_valid, Some(true));
+}
+
+#[test]
+fn unprotected() {
+    let mut frame = vec![0; 417];
+    frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0xc0]);
+    let buf = [frame.clone(), frame].concat();
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames[0].crc_checksum, None);
+    assert_eq!(meta.frames[0].crc_valid, None);
+}
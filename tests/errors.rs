@@ -0,0 +1,138 @@
+extern crate mp3_metadata;
+
+use std::error::Error;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use mp3_metadata::{ErrorKind, ParseOptions};
+
+/// Claims to be longer than it is, like a file truncated while being read.
+struct Shrunk {
+    inner: Cursor<Vec<u8>>,
+}
+
+impl Read for Shrunk {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl Seek for Shrunk {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match pos {
+            SeekFrom::End(offset) => self.inner.seek(SeekFrom::End(offset + 1000)),
+            pos => self.inner.seek(pos),
+        }
+    }
+}
+
+/// Claims to be 5 GiB long.
+struct Huge {
+    inner: Cursor<Vec<u8>>,
+}
+
+impl Read for Huge {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl Seek for Huge {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match pos {
+            SeekFrom::End(_) => Ok(5 << 30),
+            pos => self.inner.seek(pos),
+        }
+    }
+}
+
+#[test]
+fn file_error() {
+    let error = mp3_metadata::read_from_file("assets/missing.mp3").expect_err("file found");
+    assert_eq!(error.kind(), ErrorKind::FileError);
+    let source = error.source().expect("no source");
+    assert!(error.to_string().ends_with(&source.to_string()));
+}
+
+#[test]
+fn truncated() {
+    let buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let error = mp3_metadata::read_from_reader(Shrunk {
+        inner: Cursor::new(buf),
+    })
+    .expect_err("read_from_reader succeeded");
+    assert_eq!(error.kind(), ErrorKind::Truncated);
+    assert!(error.offset().is_some());
+    assert!(error.source().is_some());
+}
+
+#[test]
+fn bad_sync() {
+    // A frame header followed by neither a frame nor the end of the data.
+    let mut buf = vec![0; 2000];
+    buf[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0xc0]);
+    let error = mp3_metadata::read_from_slice(&buf).expect_err("read_from_slice succeeded");
+    assert_eq!(error.kind(), ErrorKind::BadSync);
+    assert_eq!(error.offset(), Some(0));
+
+    let error = mp3_metadata::read_from_slice(&[0; 2000]).expect_err("read_from_slice succeeded");
+    assert_eq!(error.kind(), ErrorKind::NotMP3);
+    assert_eq!(error.offset(), None);
+    assert_eq!(error.to_string(), "The file is not a valid MP3 file");
+}
+
+#[test]
+fn context() {
+    let error =
+        mp3_metadata::read_from_file("assets/double_id.mp3").expect_err("read_from_file succeeded");
+    assert_eq!(error.kind(), ErrorKind::DuplicatedIDV3);
+    assert_eq!(error.context(), Some("ID3v1 tag"));
+    let offset = error.offset().expect("no offset");
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "The MP3 file contains a duplicate IDv3 frame while reading the ID3v1 tag at offset {}",
+            offset
+        )
+    );
+}
+
+#[test]
+fn bad_tags() {
+    let audio = std::fs::read("assets/test.mp3").expect("read failed");
+    // ID3v2.3 tag with an extended header shorter than its own size field, and ID3v2.5 tag.
+    let headers: [(&[u8], ErrorKind); 2] = [
+        (
+            b"ID3\x03\x00\x40\x00\x00\x00\x10\x00\x00\x00\x02",
+            ErrorKind::BadTagHeader,
+        ),
+        (
+            b"ID3\x05\x00\x00\x00\x00\x00\x10",
+            ErrorKind::UnsupportedVersion,
+        ),
+    ];
+    for (header, kind) in headers.iter() {
+        let mut buf = header.to_vec();
+        buf.resize(26, 0);
+        buf.extend(&audio);
+        let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+        assert_eq!(meta.frames.len(), 475);
+        assert!(meta.optional_info.is_empty());
+
+        let options = ParseOptions::new().bad_tag_error(true);
+        let error = mp3_metadata::read_from_slice_with(&buf, &options)
+            .expect_err("read_from_slice_with succeeded");
+        assert_eq!(error.kind(), *kind);
+        assert_eq!(error.offset(), Some(0));
+        assert_eq!(error.context(), Some("ID3v2 tag"));
+    }
+}
+
+#[test]
+fn too_large() {
+    let buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let error = mp3_metadata::read_from_reader(Huge {
+        inner: Cursor::new(buf),
+    })
+    .expect_err("read_from_reader succeeded");
+    assert_eq!(error.kind(), ErrorKind::TooLarge);
+}
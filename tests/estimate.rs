@@ -0,0 +1,90 @@
+extern crate mp3_metadata;
+
+use std::io::Cursor;
+
+use mp3_metadata::{Confidence, ParseOptions};
+
+/// MPEG-1 Layer III, 44100 Hz, mono frames without CRC, at 128 kb/s (0x90) or
+/// 160 kb/s (0xa0).
+fn frames(bitrates: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for bitrate in bitrates {
+        let mut frame = vec![0; if *bitrate == 0x90 { 417 } else { 522 }];
+        frame[..4].copy_from_slice(&[0xff, 0xfb, *bitrate, 0xc0]);
+        buf.extend(frame);
+    }
+    buf
+}
+
+#[test]
+fn cbr() {
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    let estimate = mp3_metadata::estimate_duration_from_file("assets/test.mp3")
+        .expect("estimate_duration_from_file failed");
+    assert_eq!(estimate.duration, meta.duration);
+    assert_eq!(estimate.confidence, Confidence::Medium);
+
+    // The ID3v2 tag isn't part of the audio data.
+    let buf = std::fs::read("assets/id3v2.mp3").expect("read failed");
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let estimate =
+        mp3_metadata::estimate_duration_from_reader(Cursor::new(&buf)).expect("estimate failed");
+    assert_eq!(estimate.duration, meta.duration);
+}
+
+#[test]
+fn xing() {
+    let audio = std::fs::read("assets/test.mp3").expect("read failed");
+    let mut buf = frames(&[0x90]);
+    // Xing header claiming 1000 frames.
+    buf[21..29].copy_from_slice(b"Xing\0\0\0\x01");
+    buf[29..33].copy_from_slice(&1000u32.to_be_bytes());
+    buf.extend(&audio);
+    let estimate = mp3_metadata::estimate_duration_from_slice(&buf).expect("estimate failed");
+    assert_eq!(estimate.confidence, Confidence::High);
+    let frame_duration = mp3_metadata::read_from_slice(&audio)
+        .expect("read_from_slice failed")
+        .frames[0]
+        .duration
+        .expect("no duration");
+    assert_eq!(estimate.duration, frame_duration * 1001);
+}
+
+#[test]
+fn vbr_without_header() {
+    let buf = frames(&[0x90, 0xa0, 0x90, 0x90, 0xa0, 0xa0, 0x90, 0x90, 0x90, 0x90]);
+    let estimate = mp3_metadata::estimate_duration_from_slice(&buf).expect("estimate failed");
+    assert_eq!(estimate.confidence, Confidence::Low);
+    // Computed as if all the frames were at 128 kb/s.
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let frame_duration = meta.frames[0].duration.expect("no duration");
+    assert_eq!(estimate.duration, frame_duration * 11);
+}
+
+#[test]
+fn short_stream() {
+    let buf = frames(&[0x90, 0xa0, 0x90]);
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let estimate = mp3_metadata::estimate_duration_from_slice(&buf).expect("estimate failed");
+    assert_eq!(estimate.duration, meta.duration);
+    assert_eq!(estimate.confidence, Confidence::High);
+
+    let error =
+        mp3_metadata::estimate_duration_from_slice(&[0; 1000]).expect_err("estimate succeeded");
+    assert_eq!(error.kind(), mp3_metadata::ErrorKind::NotMP3);
+}
+
+#[test]
+fn options() {
+    // The second ID3v1 tag doesn't matter to the estimate.
+    let estimate = mp3_metadata::estimate_duration_from_file("assets/double_id.mp3")
+        .expect("estimate_duration_from_file failed");
+    assert_eq!(estimate.confidence, Confidence::Medium);
+
+    // Neither do the frames being dropped from the metadata.
+    let options = ParseOptions::new().frames(false).max_frames(1);
+    let estimate = mp3_metadata::estimate_duration_from_file_with("assets/test.mp3", &options)
+        .expect("estimate_duration_from_file_with failed");
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    assert_eq!(estimate.duration, meta.duration);
+}
@@ -0,0 +1,76 @@
+extern crate mp3_metadata;
+
+use std::io::Cursor;
+
+use mp3_metadata::{ErrorKind, Event, FrameIter, ReaderFrameIter};
+
+#[test]
+fn same_as_metadata() {
+    for path in ["assets/test.mp3", "assets/id3v2.mp3"].iter() {
+        let buf = std::fs::read(path).expect("read failed");
+        let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+        let mut frames = 0;
+        let mut id3v2 = 0;
+        for event in FrameIter::new(&buf) {
+            match event.expect("FrameIter failed") {
+                Event::Frame(frame) => {
+                    assert_eq!(frame, meta.frames[frames]);
+                    frames += 1;
+                }
+                Event::Id3v1(tag) => assert_eq!(Some(tag), meta.tag),
+                Event::Id3v2(tag) => {
+                    assert_eq!(*tag, meta.optional_info[id3v2]);
+                    id3v2 += 1;
+                }
+                event => panic!("unexpected {:?}", event),
+            }
+        }
+        assert_eq!(frames, meta.frames.len());
+        assert_eq!(id3v2, meta.optional_info.len());
+    }
+}
+
+#[test]
+fn file_order() {
+    let buf = std::fs::read("assets/id3v2.mp3").expect("read failed");
+    let events = FrameIter::new(&buf)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("FrameIter failed");
+    assert!(matches!(events.first(), Some(Event::Id3v2(_))));
+    assert!(matches!(events[1], Event::Frame(_)));
+    let buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let events = FrameIter::new(&buf)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("FrameIter failed");
+    // The trailing ID3v1 tag comes after the frames.
+    assert!(matches!(events.last(), Some(Event::Id3v1(_))));
+    assert!(matches!(events[events.len() - 2], Event::Frame(_)));
+}
+
+#[test]
+fn reader() {
+    let buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let from_slice = FrameIter::new(&buf)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("FrameIter failed");
+    let from_reader = ReaderFrameIter::new(Cursor::new(&buf))
+        .expect("ReaderFrameIter::new failed")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("ReaderFrameIter failed");
+    assert_eq!(from_slice, from_reader);
+}
+
+#[test]
+fn stops_after_error() {
+    let buf = std::fs::read("assets/double_id.mp3").expect("read failed");
+    let mut iter = FrameIter::new(&buf);
+    let error = iter
+        .by_ref()
+        .find_map(|event| event.err())
+        .expect("no error");
+    assert_eq!(error.kind(), ErrorKind::DuplicatedIDV3);
+    // Offset of the second ID3v1 tag.
+    assert!(error.offset().is_some());
+    assert_eq!(error.context(), Some("ID3v1 tag"));
+    assert!(iter.next().is_none());
+}
@@ -0,0 +1,54 @@
+extern crate mp3_metadata;
+
+/// `count` frames of `size` bytes, all zeros after the header. Every other frame is
+/// padded with `padding` bytes.
+fn stream(header: [u8; 4], size: usize, padding: usize, count: usize) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for n in 0..count {
+        let mut frame = vec![0; size];
+        frame[..4].copy_from_slice(&header);
+        if n % 2 == 1 {
+            frame[2] |= 0x02;
+            frame.extend(vec![0; padding]);
+        }
+        buf.extend(frame);
+    }
+    buf
+}
+
+#[test]
+fn layer1() {
+    // MPEG-1 Layer I, 128 kb/s, 44100 Hz: 34 slots of 4 bytes.
+    let buf = stream([0xff, 0xff, 0x40, 0xc0], 136, 4, 10);
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), 10);
+    assert_eq!(meta.frames[0].layer, mp3_metadata::Layer::Layer1);
+    assert_eq!(meta.frames[0].size, 136);
+    assert_eq!(meta.frames[1].size, 140);
+    assert_eq!(meta.frames[2].offset, 276);
+    assert_eq!(meta.total_samples, 10 * 384);
+}
+
+#[test]
+fn mpeg2_layer3() {
+    // MPEG-2 Layer III, 64 kb/s, 22050 Hz: 72 * 64000 / 22050.
+    let buf = stream([0xff, 0xf3, 0x80, 0xc0], 208, 1, 10);
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), 10);
+    assert_eq!(meta.frames[0].size, 208);
+    assert_eq!(meta.frames[1].size, 209);
+    assert_eq!(meta.total_samples, 10 * 576);
+}
+
+#[test]
+fn free_format() {
+    // MPEG-1 Layer III, free format, 44100 Hz.
+    let buf = stream([0xff, 0xfb, 0x00, 0xc0], 836, 1, 10);
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), 10);
+    assert!(meta.frames.iter().all(|frame| frame.bitrate == 256));
+    assert_eq!(meta.frames[0].size, 836);
+    assert_eq!(meta.frames[1].size, 837);
+    assert_eq!(meta.frames[9].size, 837);
+    assert_eq!(meta.frames[9].offset as usize + 837, buf.len());
+}
@@ -0,0 +1,82 @@
+extern crate mp3_metadata;
+
+/// ID3v2.3 tag holding a single "COMM" frame.
+fn id3v2_comment(description: &str, text: &str) -> Vec<u8> {
+    let mut comm = vec![0];
+    comm.extend(b"eng");
+    comm.extend(description.as_bytes());
+    comm.push(0);
+    comm.extend(text.as_bytes());
+
+    let mut frame = b"COMM".to_vec();
+    frame.extend(&(comm.len() as u32).to_be_bytes());
+    frame.extend(&[0, 0]);
+    frame.extend(comm);
+
+    let size = frame.len() as u32;
+    let mut tag = b"ID3\x03\x00\x00".to_vec();
+    tag.extend(&[
+        (size >> 21) as u8 & 0x7f,
+        (size >> 14) as u8 & 0x7f,
+        (size >> 7) as u8 & 0x7f,
+        size as u8 & 0x7f,
+    ]);
+    tag.extend(frame);
+    tag
+}
+
+#[test]
+fn samples() {
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    assert_eq!(meta.total_samples, 475 * 1152);
+    assert_eq!(meta.frames[0].first_sample, 0);
+    assert_eq!(meta.frames[10].first_sample, 10 * 1152);
+    assert_eq!(meta.gapless_info(), None);
+    assert_eq!(meta.gapless_samples(), None);
+    assert_eq!(meta.gapless_duration(), None);
+}
+
+#[test]
+fn itunsmpb() {
+    let mut buf = id3v2_comment(
+        "iTunSMPB",
+        " 00000000 00000210 000003C4 0000000000085AAC 00000000 00000000",
+    );
+    buf.extend(std::fs::read("assets/test.mp3").expect("read failed"));
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let comment = &meta.optional_info[0].comments[0];
+    assert_eq!(comment.language, "eng");
+    assert_eq!(comment.description, "iTunSMPB");
+    assert_eq!(
+        meta.gapless_info(),
+        Some(mp3_metadata::GaplessInfo {
+            delay: 528,
+            padding: 964,
+            source: mp3_metadata::GaplessSource::ITunSMPB,
+        })
+    );
+    assert_eq!(meta.gapless_samples(), Some(meta.total_samples - 528 - 964));
+    let duration = meta.gapless_duration().expect("no gapless duration");
+
+    // The sampling frequency doesn't come from the frames.
+    let options = mp3_metadata::ParseOptions::new().frames(false);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice failed");
+    assert!(meta.frames.is_empty());
+    assert_eq!(meta.gapless_duration(), Some(duration));
+}
+
+#[test]
+fn other_comment() {
+    let mut buf = id3v2_comment("", "just a comment");
+    buf.extend(std::fs::read("assets/test.mp3").expect("read failed"));
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(
+        meta.optional_info[0].comments,
+        vec![mp3_metadata::Comment {
+            language: "eng".to_owned(),
+            description: String::new(),
+            text: "just a comment".to_owned(),
+        }]
+    );
+    assert_eq!(meta.gapless_info(), None);
+}
@@ -0,0 +1,81 @@
+extern crate mp3_metadata;
+
+fn id3v1_tag(title: &[u8], comment: &[u8], track: u8) -> Vec<u8> {
+    let mut tag = vec![0; 128];
+    tag[..3].copy_from_slice(b"TAG");
+    tag[3..3 + title.len()].copy_from_slice(title);
+    tag[93..97].copy_from_slice(b"1999");
+    tag[97..97 + comment.len()].copy_from_slice(comment);
+    tag[126] = track;
+    tag[127] = 12;
+    tag
+}
+
+fn audio() -> Vec<u8> {
+    let mut buf = std::fs::read("assets/test.mp3").expect("read failed");
+    // Remove the original ID3v1 tag.
+    buf.truncate(buf.len() - 128);
+    buf
+}
+
+#[test]
+fn id3v1_track_and_padding() {
+    let mut buf = audio();
+    buf.extend(id3v1_tag(b"Title   ", b"comment\0garbage", 7));
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let tag = meta.tag.expect("no tag");
+    assert_eq!(tag.title, "Title");
+    assert_eq!(tag.comment, "comment");
+    assert_eq!(tag.year, 1999);
+    assert_eq!(tag.track, Some(7));
+    assert_eq!(tag.genre, mp3_metadata::Genre::Other);
+
+    let mut buf = audio();
+    buf.extend(id3v1_tag(b"Title", b"comment", 0));
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.tag.expect("no tag").track, None);
+}
+
+#[test]
+fn id3v1_codepage() {
+    let mut buf = audio();
+    // "Привет" in Windows-1251.
+    buf.extend(id3v1_tag(&[0xcf, 0xf0, 0xe8, 0xe2, 0xe5, 0xf2], b"", 0));
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.tag.expect("no tag").title, "Ïðèâåò");
+
+    let options = mp3_metadata::ParseOptions::new().codepage(mp3_metadata::Codepage::Windows1251);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice failed");
+    assert_eq!(meta.tag.expect("no tag").title, "Привет");
+}
+
+#[test]
+fn id3v1_extended() {
+    let audio = audio();
+    let frames = mp3_metadata::read_from_slice(&audio)
+        .expect("read_from_slice failed")
+        .frames
+        .len();
+
+    let mut ext = vec![0; 227];
+    ext[..4].copy_from_slice(b"TAG+");
+    ext[4..4 + 9].copy_from_slice(b" too long");
+    ext[184] = 3;
+    ext[185..185 + 11].copy_from_slice(b"Psybient\0\0\0");
+    ext[215..221].copy_from_slice(b"000:12");
+    ext[221..227].copy_from_slice(b"004:01");
+
+    let mut buf = audio;
+    buf.extend(ext);
+    buf.extend(id3v1_tag(b"This is a title that is really", b"", 0));
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), frames);
+    let tag = meta.tag.expect("no tag");
+    assert_eq!(tag.title, "This is a title that is really too long");
+    assert_eq!(tag.artist, "");
+    assert_eq!(tag.speed, Some(3));
+    assert_eq!(tag.extended_genre, Some("Psybient".to_owned()));
+    assert_eq!(tag.start_time, Some("000:12".to_owned()));
+    assert_eq!(tag.end_time, Some("004:01".to_owned()));
+}
@@ -52,11 +52,28 @@ fn id3v2() {
         meta.tag,
         Some(mp3_metadata::AudioTag {
             title: "This is a wonderful title isn'".to_owned(),
-            artist: "Someone/Someone else          ".to_owned(),
-            album: "".to_owned(),
+            artist: "Someone/Someone else".to_owned(),
+            album: "éàµ£ø§".to_owned(),
             year: 2015,
-            comment: "Some random comment because ".to_owned(),
+            comment: "Some random comment because".to_owned(),
+            track: Some(1),
             genre: mp3_metadata::Genre::Other,
+            speed: None,
+            extended_genre: None,
+            start_time: None,
+            end_time: None,
         })
     );
 }
+
+#[test]
+fn utf16be_text() {
+    // ID3v2.4 tag with a title in UTF-16BE without BOM.
+    let frame = b"TIT2\x00\x00\x00\x07\x00\x00\x02\x00H\x00i\x00!";
+    let mut buf = b"ID3\x04\x00\x00\x00\x00\x00".to_vec();
+    buf.push(frame.len() as u8);
+    buf.extend(frame.iter());
+    buf.extend(std::fs::read("assets/test.mp3").expect("read failed"));
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.optional_info[0].title, Some("Hi!".to_owned()));
+}
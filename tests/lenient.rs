@@ -0,0 +1,157 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::{ErrorKind, ParseOptions, Severity};
+
+fn audio() -> (Vec<u8>, Vec<u8>) {
+    let mut buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let id3v1 = buf.split_off(buf.len() - 128);
+    (buf, id3v1)
+}
+
+#[test]
+fn clean() {
+    let (meta, diagnostics) =
+        mp3_metadata::read_from_file_lenient("assets/test.mp3").expect("lenient read failed");
+    assert_eq!(diagnostics, Vec::new());
+    assert_eq!(
+        meta,
+        mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed")
+    );
+}
+
+#[test]
+fn duplicated_id3v1() {
+    let (meta, diagnostics) =
+        mp3_metadata::read_from_file_lenient("assets/double_id.mp3").expect("lenient read failed");
+    assert!(!meta.frames.is_empty());
+    assert!(meta.tag.is_some());
+    let duplicate = diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.kind == ErrorKind::DuplicatedIDV3)
+        .expect("no duplicate reported");
+    assert_eq!(duplicate.severity, Severity::Error);
+
+    // Still recorded when it isn't an error.
+    let options = ParseOptions::new().duplicate_id3v1_error(false);
+    let (_, diagnostics) =
+        mp3_metadata::read_from_file_lenient_with("assets/double_id.mp3", &options)
+            .expect("lenient read failed");
+    let duplicate = diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.kind == ErrorKind::DuplicatedIDV3)
+        .expect("no duplicate reported");
+    assert_eq!(duplicate.severity, Severity::Warning);
+    assert_eq!(duplicate.message, "Second ID3v1 tag");
+}
+
+#[test]
+fn garbage_and_truncation() {
+    let (audio, _) = audio();
+    // 100 bytes of garbage after the second frame, and half of the last frame.
+    let second_end = 417 + 418;
+    let mut buf = audio[..second_end].to_vec();
+    buf.extend(vec![0; 100]);
+    buf.extend(&audio[second_end..audio.len() - 200]);
+
+    let (meta, diagnostics) =
+        mp3_metadata::read_from_slice_lenient(&buf).expect("lenient read failed");
+    assert_eq!(meta.frames.len(), 475);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].kind, ErrorKind::BadSync);
+    assert_eq!(diagnostics[0].offset, second_end as u64);
+    assert_eq!(
+        diagnostics[0].message,
+        "100 bytes are neither a frame nor a tag"
+    );
+    assert_eq!(diagnostics[1].kind, ErrorKind::Truncated);
+    assert_eq!(diagnostics[1].offset, meta.frames[474].offset as u64);
+}
+
+#[test]
+fn unsupported_id3v2_version() {
+    let (audio, id3v1) = audio();
+    let mut buf = b"ID3\x05\x00\x00\x00\x00\x00\x10".to_vec();
+    buf.extend(vec![0; 16]);
+    buf.extend(audio);
+    buf.extend(id3v1);
+
+    let (meta, diagnostics) =
+        mp3_metadata::read_from_slice_lenient(&buf).expect("lenient read failed");
+    assert_eq!(meta.frames.len(), 475);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].kind, ErrorKind::UnsupportedVersion);
+    assert_eq!(diagnostics[0].offset, 0);
+    assert_eq!(diagnostics[0].message, "ID3v2.5 tag");
+}
+
+#[test]
+fn undecodable_text() {
+    let (audio, id3v1) = audio();
+    // Appended ID3v2.4 tag with a title which isn't valid UTF-8.
+    let frame = b"TIT2\x00\x00\x00\x03\x00\x00\x03\xff\xfe";
+    let size = [0, 0, 0, frame.len() as u8];
+    let mut buf = audio;
+    buf.extend(b"ID3\x04\x00\x10");
+    buf.extend(&size);
+    buf.extend(frame.iter());
+    buf.extend(b"3DI\x04\x00\x10");
+    buf.extend(&size);
+    buf.extend(id3v1);
+
+    let (meta, diagnostics) =
+        mp3_metadata::read_from_slice_lenient(&buf).expect("lenient read failed");
+    assert_eq!(meta.optional_info.len(), 1);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, ErrorKind::InvalidData);
+    assert_eq!(diagnostics[0].offset, 198530);
+    assert!(diagnostics[0].message.contains("TIT2"));
+}
+
+#[test]
+fn utf16be_text() {
+    let (audio, id3v1) = audio();
+    // ID3v2.4 tag with a title in UTF-16BE without BOM.
+    let frame = b"TIT2\x00\x00\x00\x07\x00\x00\x02\x00H\x00i\x00!";
+    let mut buf = b"ID3\x04\x00\x00\x00\x00\x00".to_vec();
+    buf.push(frame.len() as u8);
+    buf.extend(frame.iter());
+    buf.extend(audio);
+    buf.extend(id3v1);
+
+    let (meta, diagnostics) =
+        mp3_metadata::read_from_slice_lenient(&buf).expect("lenient read failed");
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.kind != ErrorKind::InvalidData));
+    assert_eq!(meta.optional_info[0].title, Some("Hi!".to_owned()));
+}
+
+#[test]
+fn broken_ape_header() {
+    let (mut buf, id3v1) = audio();
+    let end = buf.len();
+    // APE header claiming 1000 bytes of items, while only the ID3v1 tag follows.
+    buf.extend(b"APETAGEX");
+    buf.extend(&2000u32.to_le_bytes());
+    buf.extend(&1000u32.to_le_bytes());
+    buf.extend(&1u32.to_le_bytes());
+    buf.extend(&((1u32 << 31) | (1 << 29)).to_le_bytes());
+    buf.extend(&[0; 8]);
+    buf.extend(id3v1);
+
+    let (meta, diagnostics) =
+        mp3_metadata::read_from_slice_lenient(&buf).expect("lenient read failed");
+    assert_eq!(meta.frames.len(), 475);
+    assert_eq!(meta.ape, None);
+    assert!(meta.tag.is_some());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, ErrorKind::BadTagHeader);
+    assert_eq!(diagnostics[0].offset, end as u64);
+
+    let options = ParseOptions::new().bad_tag_error(true);
+    let (_, diagnostics) =
+        mp3_metadata::read_from_slice_lenient_with(&buf, &options).expect("lenient read failed");
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+}
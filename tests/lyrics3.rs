@@ -0,0 +1,75 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::Lyrics3Version;
+
+fn audio() -> (Vec<u8>, Vec<u8>) {
+    let mut buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let id3v1 = buf.split_off(buf.len() - 128);
+    (buf, id3v1)
+}
+
+fn field(id: &str, value: &str) -> Vec<u8> {
+    format!("{}{:05}{}", id, value.len(), value).into_bytes()
+}
+
+#[test]
+fn lyrics3_v2() {
+    let (mut buf, id3v1) = audio();
+    let frames = mp3_metadata::read_from_slice(&buf)
+        .expect("read_from_slice failed")
+        .frames
+        .len();
+
+    let mut tag = b"LYRICSBEGIN".to_vec();
+    tag.extend(field("IND", "10"));
+    tag.extend(field("LYR", "[00:01]Hello\r\n[00:03]World"));
+    tag.extend(field("EAR", "An artist with a very long name"));
+    tag.extend(field("XYZ", "unknown"));
+    let size = format!("{:06}", tag.len());
+    buf.extend(tag);
+    buf.extend(size.as_bytes());
+    buf.extend(b"LYRICS200");
+    buf.extend(id3v1);
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), frames);
+    assert!(meta.tag.is_some());
+    let lyrics3 = meta.lyrics3.expect("no Lyrics3 tag");
+    assert_eq!(lyrics3.version, Lyrics3Version::V2);
+    assert_eq!(lyrics3.indications, Some("10".to_owned()));
+    assert_eq!(
+        lyrics3.lyrics,
+        Some("[00:01]Hello\r\n[00:03]World".to_owned())
+    );
+    assert_eq!(
+        lyrics3.artist,
+        Some("An artist with a very long name".to_owned())
+    );
+    assert_eq!(lyrics3.title, None);
+}
+
+#[test]
+fn lyrics3_v1() {
+    let (mut buf, id3v1) = audio();
+    let frames = mp3_metadata::read_from_slice(&buf)
+        .expect("read_from_slice failed")
+        .frames
+        .len();
+
+    buf.extend(b"LYRICSBEGINSome lyrics\r\nwith two lines LYRICSEND");
+    let without_id3v1 = buf.clone();
+    buf.extend(id3v1);
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), frames);
+    let lyrics3 = meta.lyrics3.expect("no Lyrics3 tag");
+    assert_eq!(lyrics3.version, Lyrics3Version::V1);
+    assert_eq!(
+        lyrics3.lyrics,
+        Some("Some lyrics\r\nwith two lines ".to_owned())
+    );
+
+    // Lyrics3 tags are only valid when followed by an ID3v1 tag.
+    let meta = mp3_metadata::read_from_slice(&without_id3v1).expect("read_from_slice failed");
+    assert_eq!(meta.lyrics3, None);
+}
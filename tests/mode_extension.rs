@@ -0,0 +1,49 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::ModeExtension;
+
+fn read(header: [u8; 4]) -> Option<ModeExtension> {
+    // 128 kb/s, 44100 Hz, for both Layer II and Layer III.
+    let mut frame = vec![0; 417];
+    frame[..4].copy_from_slice(&header);
+    let buf = [frame.clone(), frame].concat();
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len(), 2);
+    meta.frames[0].mode_extension
+}
+
+#[test]
+fn layer3() {
+    assert_eq!(
+        read([0xff, 0xfb, 0x90, 0x60]),
+        Some(ModeExtension::Layer3 {
+            intensity_stereo: false,
+            ms_stereo: true,
+        })
+    );
+    assert_eq!(
+        read([0xff, 0xfb, 0x90, 0x70]),
+        Some(ModeExtension::Layer3 {
+            intensity_stereo: true,
+            ms_stereo: true,
+        })
+    );
+}
+
+#[test]
+fn layer2() {
+    assert_eq!(
+        read([0xff, 0xfd, 0x80, 0x50]),
+        Some(ModeExtension::IntensityBound(8))
+    );
+    assert_eq!(
+        read([0xff, 0xfd, 0x80, 0x70]),
+        Some(ModeExtension::IntensityBound(16))
+    );
+}
+
+#[test]
+fn not_joint_stereo() {
+    // Stereo, with mode extension bits set anyway.
+    assert_eq!(read([0xff, 0xfb, 0x90, 0x30]), None);
+}
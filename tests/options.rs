@@ -0,0 +1,128 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::{ErrorKind, ParseOptions};
+
+fn ape_header(size: usize, flags: u32) -> Vec<u8> {
+    let mut header = b"APETAGEX".to_vec();
+    header.extend(&2000u32.to_le_bytes());
+    header.extend(&(size as u32).to_le_bytes());
+    header.extend(&1u32.to_le_bytes());
+    header.extend(&flags.to_le_bytes());
+    header.extend(&[0; 8]);
+    header
+}
+
+/// test.mp3 with an APE tag holding a title of `title_len` bytes and a Lyrics3v2 tag
+/// before its ID3v1 tag.
+fn tagged_audio(title_len: usize) -> Vec<u8> {
+    let mut buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let id3v1 = buf.split_off(buf.len() - 128);
+    let mut item = (title_len as u32).to_le_bytes().to_vec();
+    item.extend(&[0; 4]);
+    item.extend(b"Title\0");
+    item.extend(vec![b'a'; title_len]);
+    buf.extend(ape_header(item.len() + 32, (1 << 31) | (1 << 29)));
+    buf.extend(&item);
+    buf.extend(ape_header(item.len() + 32, 1 << 31));
+    let lyrics = b"LYRICSBEGINLYR00005Hello";
+    buf.extend(lyrics.iter());
+    buf.extend(format!("{:06}LYRICS200", lyrics.len()).as_bytes());
+    buf.extend(id3v1);
+    buf
+}
+
+#[test]
+fn defaults() {
+    let buf = tagged_audio(10);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &ParseOptions::new())
+        .expect("read_from_slice_with failed");
+    assert_eq!(meta.frames.len(), 475);
+    assert!(meta.tag.is_some());
+    assert!(meta.ape.is_some());
+    assert!(meta.lyrics3.is_some());
+}
+
+#[test]
+fn tag_families() {
+    let buf = tagged_audio(10);
+    let options = ParseOptions::new().id3v1(false).ape(false).lyrics3(false);
+    let meta =
+        mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice_with failed");
+    // The tags are still skipped.
+    assert_eq!(meta.frames.len(), 475);
+    assert_eq!(meta.resyncs, Vec::<u32>::new());
+    assert_eq!(meta.tag, None);
+    assert_eq!(meta.ape, None);
+    assert_eq!(meta.lyrics3, None);
+
+    let buf = std::fs::read("assets/id3v2.mp3").expect("read failed");
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let options = ParseOptions::new().id3v2(false);
+    let without =
+        mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice_with failed");
+    assert!(!meta.optional_info.is_empty());
+    assert!(without.optional_info.is_empty());
+    assert_eq!(without.frames, meta.frames);
+}
+
+#[test]
+fn max_tag_size() {
+    let buf = tagged_audio(1000);
+    let options = ParseOptions::new().max_tag_size(500);
+    let meta =
+        mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice_with failed");
+    assert_eq!(meta.frames.len(), 475);
+    assert!(meta.tag.is_some());
+    assert_eq!(meta.ape, None);
+    assert!(meta.lyrics3.is_some());
+
+    let buf = std::fs::read("assets/id3v2.mp3").expect("read failed");
+    let meta =
+        mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice_with failed");
+    assert!(meta.optional_info.is_empty());
+    let tags = mp3_metadata::read_tags_from_slice_with(&buf, &options)
+        .expect("read_tags_from_slice_with failed");
+    assert!(tags.optional_info.is_empty());
+}
+
+#[test]
+fn frames() {
+    let full = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    let options = ParseOptions::new().frames(false);
+    let meta = mp3_metadata::read_from_file_with("assets/test.mp3", &options)
+        .expect("read_from_file_with failed");
+    assert!(meta.frames.is_empty());
+    assert_eq!(meta.duration, full.duration);
+    assert_eq!(meta.summary, full.summary);
+    assert_eq!(meta.tag, full.tag);
+}
+
+#[test]
+fn max_frames() {
+    let full = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    for max_frames in [1, 10].iter() {
+        let options = ParseOptions::new().max_frames(*max_frames);
+        let meta = mp3_metadata::read_from_file_with("assets/test.mp3", &options)
+            .expect("read_from_file_with failed");
+        assert_eq!(meta.frames, full.frames[..*max_frames as usize]);
+        assert_eq!(meta.summary.frames, *max_frames);
+        // The tags at the end are still read.
+        assert_eq!(meta.tag, full.tag);
+    }
+}
+
+#[test]
+fn duplicate_id3v1() {
+    let error =
+        mp3_metadata::read_from_file("assets/double_id.mp3").expect_err("read_from_file succeeded");
+    assert_eq!(error.kind(), ErrorKind::DuplicatedIDV3);
+    let options = ParseOptions::new().duplicate_id3v1_error(false);
+    let meta = mp3_metadata::read_from_file_with("assets/double_id.mp3", &options)
+        .expect("read_from_file_with failed");
+    assert!(meta.tag.is_some());
+
+    let options = ParseOptions::new().id3v1(false);
+    let meta = mp3_metadata::read_from_file_with("assets/double_id.mp3", &options)
+        .expect("read_from_file_with failed");
+    assert_eq!(meta.tag, None);
+}
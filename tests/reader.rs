@@ -0,0 +1,90 @@
+extern crate mp3_metadata;
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+/// Keeps track of the largest read.
+struct Tracker {
+    inner: Cursor<Vec<u8>>,
+    largest_read: usize,
+}
+
+impl Read for Tracker {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.largest_read = self.largest_read.max(read);
+        Ok(read)
+    }
+}
+
+impl Seek for Tracker {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn same_as_slice() {
+    for path in [
+        "assets/test.mp3",
+        "assets/id3v2.mp3",
+        "assets/trunc_test.mp3",
+    ]
+    .iter()
+    {
+        let buf = std::fs::read(path).expect("read failed");
+        assert_eq!(
+            mp3_metadata::read_from_reader(Cursor::new(&buf)).expect("read_from_reader failed"),
+            mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed"),
+            "{}",
+            path
+        );
+    }
+    let buf = std::fs::read("assets/double_id.mp3").expect("read failed");
+    let error =
+        mp3_metadata::read_from_reader(Cursor::new(&buf)).expect_err("read_from_reader succeeded");
+    assert_eq!(error.kind(), mp3_metadata::ErrorKind::DuplicatedIDV3);
+}
+
+#[test]
+fn bounded_reads() {
+    // An ID3v2 tag, then 20 times the audio of test.mp3 with junk in between, and an
+    // APE tag before the ID3v1 tag.
+    let test = std::fs::read("assets/test.mp3").expect("read failed");
+    let (audio, id3v1) = test.split_at(test.len() - 128);
+    let id3v2 = std::fs::read("assets/id3v2.mp3").expect("read failed");
+    let mut buf = id3v2[..4096].to_vec();
+    for _ in 0..20 {
+        buf.extend(audio);
+        buf.extend(&[0x55; 1000]);
+    }
+    let mut ape = b"APETAGEX".to_vec();
+    ape.extend(&2000u32.to_le_bytes());
+    ape.extend(&(32u32 + 13).to_le_bytes());
+    ape.extend(&1u32.to_le_bytes());
+    ape.extend(&0u32.to_le_bytes());
+    ape.extend(&[0; 8]);
+    let mut item = 1u32.to_le_bytes().to_vec();
+    item.extend(&0u32.to_le_bytes());
+    item.extend(b"Key\0x");
+    buf.extend(&item);
+    buf.extend(&ape);
+    buf.extend(id3v1);
+
+    let expected = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert!(expected.frames.len() > 19 * 475);
+    assert!(!expected.optional_info.is_empty());
+    assert!(expected.ape.is_some());
+    let mut tracker = Tracker {
+        inner: Cursor::new(buf.clone()),
+        largest_read: 0,
+    };
+    let meta = mp3_metadata::read_from_reader(&mut tracker).expect("read_from_reader failed");
+    assert_eq!(meta, expected);
+    // The buffer is replaced by each read, and holds a chunk of 256 KiB.
+    assert!(buf.len() > 15 * 256 * 1024);
+    assert!(
+        tracker.largest_read <= 256 * 1024,
+        "{}",
+        tracker.largest_read
+    );
+}
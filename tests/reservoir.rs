@@ -0,0 +1,44 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::ParseOptions;
+
+#[test]
+fn dependencies() {
+    let options = ParseOptions::new().side_info(true);
+    let meta = mp3_metadata::read_from_file_with("assets/test.mp3", &options)
+        .expect("read_from_file_with failed");
+    let reservoir = meta.bit_reservoir().expect("no side info");
+    assert_eq!(reservoir.len(), meta.frames.len());
+    assert!(reservoir[0].independent);
+    assert_eq!(reservoir[0].depends_on, 0..0);
+    // The main data of the second frame starts 23 bytes before its header.
+    assert!(!reservoir[1].independent);
+    assert_eq!(reservoir[1].depends_on, 0..1);
+    assert!(reservoir.iter().all(|dependency| !dependency.before_start));
+
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    assert_eq!(meta.bit_reservoir(), None);
+
+    // Without frames.
+    let options = ParseOptions::new().side_info(true).frames(false);
+    let meta = mp3_metadata::read_from_file_with("assets/test.mp3", &options)
+        .expect("read_from_file_with failed");
+    assert_eq!(meta.bit_reservoir(), None);
+}
+
+#[test]
+fn cut() {
+    let buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let options = ParseOptions::new().side_info(true);
+    let meta =
+        mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice_with failed");
+    let offset = meta.frames[1].offset as usize;
+
+    // Cutting the stream at a frame depending on the previous one.
+    let meta = mp3_metadata::read_from_slice_with(&buf[offset..], &options)
+        .expect("read_from_slice_with failed");
+    let reservoir = meta.bit_reservoir().expect("no side info");
+    assert!(reservoir[0].before_start);
+    assert_eq!(reservoir[0].depends_on, 0..0);
+    assert!(!reservoir[1].before_start);
+}
@@ -0,0 +1,198 @@
+extern crate mp3_metadata;
+
+use std::time::Duration;
+
+use mp3_metadata::{ParseOptions, SeekIndex, SeekResult};
+
+#[test]
+fn frames() {
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    let index = meta.seek_index().expect("no seek index");
+    assert!(index.is_exact());
+    // 1152 samples at 44100 Hz: one second is in the 39th frame.
+    let frame = &meta.frames[38];
+    assert_eq!(index.frame_at(Duration::from_secs(1)), Some(38));
+    assert_eq!(
+        index.offset_for_time(Duration::from_secs(1)),
+        Some(SeekResult {
+            offset: frame.offset as u64,
+            time: frame.position,
+            exact: true,
+        })
+    );
+    assert_eq!(
+        index.time_for_offset(frame.offset as u64 + 10),
+        Some(SeekResult {
+            offset: frame.offset as u64,
+            time: frame.position,
+            exact: true,
+        })
+    );
+    assert_eq!(index.frame_at(Duration::from_secs(3600)), Some(474));
+    assert_eq!(index.frame_at(Duration::new(0, 0)), Some(0));
+}
+
+#[test]
+fn xing_toc() {
+    let mut buf = vec![0; 417];
+    buf[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0xc0]);
+    let mut xing = b"Xing".to_vec();
+    xing.extend(&0x7u32.to_be_bytes());
+    xing.extend(&100u32.to_be_bytes());
+    xing.extend(&25600u32.to_be_bytes());
+    xing.extend((0..100).map(|i| i * 2));
+    buf[21..21 + xing.len()].copy_from_slice(&xing);
+    buf.extend(std::fs::read("assets/test.mp3").expect("read failed"));
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let index =
+        SeekIndex::from_vbr_header(meta.vbr_header.as_ref().expect("no VBR header")).unwrap();
+    assert!(!index.is_exact());
+    // 100 frames of 1152 samples at 44100 Hz, half of it is at 100/256 of the bytes.
+    let duration = Duration::new(2, 612244897);
+    let half = index.offset_for_time(duration / 2).unwrap();
+    assert_eq!(half.offset, 10000);
+    assert!(!half.exact);
+    // Between two entries.
+    let result = index.offset_for_time(duration * 3 / 200).unwrap();
+    assert_eq!(result.offset, 300);
+    let result = index.time_for_offset(10000).unwrap();
+    assert_eq!(result.time, duration / 2);
+    assert_eq!(index.frame_at(duration / 2), None);
+}
+
+#[test]
+fn vbri_table() {
+    let mut buf = vec![0; 417];
+    buf[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0xc0]);
+    let mut vbri = b"VBRI".to_vec();
+    vbri.extend(&[0, 1, 0, 0, 0, 75]);
+    vbri.extend(&(417u32 + 3000).to_be_bytes());
+    vbri.extend(&300u32.to_be_bytes());
+    // 3 entries of 2 bytes, with a scale of 1, for 100 frames each.
+    vbri.extend(&[0, 3, 0, 1, 0, 2, 0, 100]);
+    vbri.extend(&[0x03, 0xe8, 0x03, 0xe8, 0x03, 0xe8]);
+    buf[36..36 + vbri.len()].copy_from_slice(&vbri);
+    buf.extend(std::fs::read("assets/test.mp3").expect("read failed"));
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let index =
+        SeekIndex::from_vbr_header(meta.vbr_header.as_ref().expect("no VBR header")).unwrap();
+    // 100 frames of 1152 samples at 44100 Hz.
+    let entry = Duration::new(2, 612244897);
+    assert_eq!(
+        index.offset_for_time(Duration::new(0, 0)).unwrap().offset,
+        417
+    );
+    assert_eq!(index.offset_for_time(entry).unwrap().offset, 1417);
+    assert_eq!(index.offset_for_time(entry * 2).unwrap().offset, 2417);
+    assert_eq!(index.time_for_offset(1917).unwrap().time, entry + entry / 2);
+}
+
+/// test.mp3 behind a 36 bytes ID3v2 tag holding an "MLLT" frame.
+fn mllt_audio() -> Vec<u8> {
+    let mut mllt = vec![0, 1, 0, 1, 0xa1, 0, 0, 26, 8, 8];
+    // Three references, one byte longer for the second one, one millisecond longer for
+    // the third one.
+    mllt.extend(&[0, 0, 1, 0, 0, 1]);
+    let mut frame = b"MLLT".to_vec();
+    frame.extend(&(mllt.len() as u32).to_be_bytes());
+    frame.extend(&[0, 0]);
+    frame.extend(mllt);
+    let mut buf = b"ID3\x03\x00\x00\x00\x00\x00".to_vec();
+    buf.push(frame.len() as u8);
+    buf.extend(frame);
+    buf.extend(std::fs::read("assets/test.mp3").expect("read failed"));
+    buf
+}
+
+#[test]
+fn mllt() {
+    let buf = mllt_audio();
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let table = meta.optional_info[0]
+        .mpeg_location_lookup_table
+        .as_ref()
+        .expect("no MLLT frame");
+    assert_eq!(table.frames_between_reference, 1);
+    assert_eq!(table.bytes_between_reference, 417);
+    assert_eq!(table.millis_between_reference, 26);
+    assert_eq!(table.references.len(), 3);
+    assert_eq!(table.references[1].bytes_deviation, 1);
+
+    let index = SeekIndex::from_mllt(table, 100).expect("no seek index");
+    assert_eq!(
+        index.offset_for_time(Duration::from_millis(52)),
+        Some(SeekResult {
+            offset: 100 + 417 * 2 + 1,
+            time: Duration::from_millis(52),
+            exact: false,
+        })
+    );
+    assert_eq!(
+        index.time_for_offset(100 + 417 * 3 + 1).unwrap().time,
+        Duration::from_millis(79)
+    );
+    // Nothing to interpolate with after the last reference.
+    assert_eq!(
+        index
+            .offset_for_time(Duration::from_secs(1))
+            .unwrap()
+            .offset,
+        100 + 417 * 3 + 1
+    );
+}
+
+#[test]
+fn mllt_seek_index() {
+    let options = ParseOptions::new().frames(false);
+    let meta = mp3_metadata::read_from_slice_with(&mllt_audio(), &options)
+        .expect("read_from_slice_with failed");
+    assert!(meta.frames.is_empty());
+    let index = meta.seek_index().expect("no seek index");
+    // The references start at the first frame, after the tag.
+    assert_eq!(
+        index.offset_for_time(Duration::new(0, 0)).unwrap().offset,
+        36
+    );
+    assert_eq!(
+        index
+            .offset_for_time(Duration::from_millis(52))
+            .unwrap()
+            .offset,
+        36 + 417 * 2 + 1
+    );
+}
+
+#[test]
+fn partial_frames() {
+    let full = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    let options = ParseOptions::new().max_frames(10);
+    let meta = mp3_metadata::read_from_file_with("assets/test.mp3", &options)
+        .expect("read_from_file_with failed");
+    assert!(meta.partial);
+    assert!(!full.partial);
+    let index = meta.seek_index().expect("no seek index");
+    assert!(!index.is_exact());
+    // Within the frames read.
+    let frame = &full.frames[5];
+    assert_eq!(
+        index.offset_for_time(frame.position),
+        Some(SeekResult {
+            offset: frame.offset as u64,
+            time: frame.position,
+            exact: true,
+        })
+    );
+    assert_eq!(index.frame_at(frame.position), Some(5));
+    // After them, only the end of the last frame read is known.
+    let end = &full.frames[10];
+    let result = Some(SeekResult {
+        offset: end.offset as u64,
+        time: end.position,
+        exact: false,
+    });
+    assert_eq!(index.offset_for_time(Duration::from_secs(5)), result);
+    assert_eq!(index.time_for_offset(100_000), result);
+    assert_eq!(index.frame_at(Duration::from_secs(5)), None);
+}
@@ -0,0 +1,39 @@
+extern crate mp3_metadata;
+
+#[test]
+fn single() {
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    assert_eq!(meta.segments.len(), 1);
+    let segment = &meta.segments[0];
+    assert_eq!(segment.frames, 0..475);
+    assert_eq!(segment.bytes.start, meta.frames[0].offset);
+    let last = meta.frames.last().unwrap();
+    assert_eq!(segment.bytes.end, last.offset + last.size);
+    assert_eq!(segment.sampling_freq, 44100);
+    assert_eq!(segment.chan_type, mp3_metadata::ChannelType::SingleChannel);
+    assert!(!meta.has_parameter_changes());
+}
+
+#[test]
+fn concatenated() {
+    let mut buf = std::fs::read("assets/test.mp3").expect("read failed");
+    // Remove the ID3v1 tag.
+    buf.truncate(buf.len() - 128);
+    let end = buf.len() as u32;
+    // MPEG-1 Layer III, 128 kb/s, 48000 Hz, stereo.
+    for _ in 0..10 {
+        let mut frame = vec![0; 384];
+        frame[..4].copy_from_slice(&[0xff, 0xfb, 0x94, 0x00]);
+        buf.extend(frame);
+    }
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert!(meta.has_parameter_changes());
+    assert_eq!(meta.segments.len(), 2);
+    assert_eq!(meta.segments[0].frames, 0..475);
+    assert_eq!(meta.segments[0].bytes.end, end);
+    let segment = &meta.segments[1];
+    assert_eq!(segment.frames, 475..485);
+    assert_eq!(segment.bytes, end..end + 3840);
+    assert_eq!(segment.sampling_freq, 48000);
+    assert_eq!(segment.chan_type, mp3_metadata::ChannelType::Stereo);
+}
@@ -0,0 +1,65 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::{BlockType, ParseOptions};
+
+#[test]
+fn mpeg1_mono() {
+    let options = ParseOptions::new().side_info(true);
+    let meta = mp3_metadata::read_from_file_with("assets/test.mp3", &options)
+        .expect("read_from_file_with failed");
+    let side_info = meta.frames[0].side_info.as_ref().expect("no side info");
+    assert_eq!(side_info.main_data_begin, 0);
+    assert_eq!(side_info.scfsi, vec![[false, true, true, false]]);
+    assert_eq!(side_info.granules.len(), 2);
+    assert_eq!(side_info.granules[0].len(), 1);
+    let granule = &side_info.granules[0][0];
+    assert_eq!(granule.part2_3_length, 1409);
+    assert_eq!(granule.big_values, 192);
+    assert_eq!(granule.global_gain, 142);
+    assert_eq!(granule.scalefac_compress, 1);
+    assert_eq!(granule.block_type, BlockType::Normal);
+    assert_eq!(granule.table_select, [15, 12, 6]);
+    assert_eq!(
+        meta.frames[1].side_info.as_ref().unwrap().main_data_begin,
+        23
+    );
+    assert!(meta.frames.iter().any(|frame| frame
+        .side_info
+        .as_ref()
+        .unwrap()
+        .granules
+        .iter()
+        .any(|granule| granule[0].block_type == BlockType::Short)));
+
+    // Not decoded by default.
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    assert_eq!(meta.frames[0].side_info, None);
+}
+
+#[test]
+fn mpeg2_stereo() {
+    // MPEG-2 Layer III, 64 kb/s, 22050 Hz, stereo, without CRC.
+    let mut frame = vec![0; 208];
+    frame[..4].copy_from_slice(&[0xff, 0xf3, 0x80, 0x00]);
+    frame[4] = 0xab;
+    // First channel: part2_3_length 100, big_values 50, global_gain 210,
+    // scalefac_compress 300 and short, mixed blocks.
+    frame[5..11].copy_from_slice(&[0x01, 0x90, 0x65, 0xa5, 0x2c, 0xd0]);
+    let buf = [frame.clone(), frame].concat();
+    let options = ParseOptions::new().side_info(true);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice failed");
+    let side_info = meta.frames[0].side_info.as_ref().expect("no side info");
+    assert_eq!(side_info.main_data_begin, 0xab);
+    assert!(side_info.scfsi.is_empty());
+    assert_eq!(side_info.granules.len(), 1);
+    assert_eq!(side_info.granules[0].len(), 2);
+    let granule = &side_info.granules[0][0];
+    assert_eq!(granule.part2_3_length, 100);
+    assert_eq!(granule.big_values, 50);
+    assert_eq!(granule.global_gain, 210);
+    assert_eq!(granule.scalefac_compress, 300);
+    assert_eq!(granule.block_type, BlockType::Short);
+    assert!(granule.mixed_block);
+    assert_eq!(granule.region0_count, 7);
+    assert_eq!(side_info.granules[0][1].block_type, BlockType::Normal);
+}
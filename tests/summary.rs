@@ -0,0 +1,39 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::BitrateMode;
+
+#[test]
+fn cbr() {
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    let summary = &meta.summary;
+    assert_eq!(summary.frames, 475);
+    assert_eq!(summary.min_bitrate, 128);
+    assert_eq!(summary.max_bitrate, 128);
+    assert_eq!(summary.bitrate_histogram[9], 475);
+    // Frames of 417 bytes, most of them padded.
+    assert_eq!(summary.bytes, 198530);
+    assert_eq!(summary.mean_bitrate(), Some(128.));
+    let average = summary.average_bitrate().expect("no duration");
+    assert!((average - 128.).abs() < 0.01, "{}", average);
+    assert_eq!(summary.bitrate_mode, BitrateMode::Cbr);
+}
+
+#[test]
+fn vbr() {
+    // MPEG-1 Layer III, 44100 Hz, mono: 128 kb/s and 160 kb/s frames.
+    let mut buf = Vec::new();
+    for bitrate in [0x90, 0xa0, 0x90, 0x90].iter() {
+        let mut frame = vec![0; if *bitrate == 0x90 { 417 } else { 522 }];
+        frame[..4].copy_from_slice(&[0xff, 0xfb, *bitrate, 0xc0]);
+        buf.extend(frame);
+    }
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let summary = &meta.summary;
+    assert_eq!(summary.frames, 4);
+    assert_eq!(summary.min_bitrate, 128);
+    assert_eq!(summary.max_bitrate, 160);
+    assert_eq!(summary.bitrate_histogram[9], 3);
+    assert_eq!(summary.bitrate_histogram[10], 1);
+    assert_eq!(summary.mean_bitrate(), Some(136.));
+    assert_eq!(summary.bitrate_mode, BitrateMode::Vbr);
+}
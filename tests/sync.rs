@@ -0,0 +1,39 @@
+extern crate mp3_metadata;
+
+use mp3_metadata::ParseOptions;
+
+/// 100 bytes of junk holding something looking like a frame header, then the audio.
+fn junk_then_audio() -> Vec<u8> {
+    let mut buf = vec![0x55; 100];
+    buf[10..14].copy_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+    buf.extend(std::fs::read("assets/test.mp3").expect("read failed"));
+    buf
+}
+
+#[test]
+fn phantom_frame() {
+    let buf = junk_then_audio();
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.resyncs, vec![10]);
+    assert_eq!(meta.frames.len(), 475);
+    assert_eq!(meta.frames[0].offset, 100);
+
+    let options = ParseOptions::new().sync_frames(0);
+    let meta = mp3_metadata::read_from_slice_with(&buf, &options).expect("read_from_slice failed");
+    assert!(meta.resyncs.is_empty());
+    assert_eq!(meta.frames[0].offset, 10);
+}
+
+#[test]
+fn more_frames() {
+    let options = ParseOptions::new().sync_frames(4);
+    let meta = mp3_metadata::read_from_file_with("assets/test.mp3", &options)
+        .expect("read_from_file_with failed");
+    assert!(meta.resyncs.is_empty());
+    assert_eq!(meta.frames.len(), 475);
+
+    let meta = mp3_metadata::read_from_slice_with(&junk_then_audio(), &options)
+        .expect("read_from_slice failed");
+    assert_eq!(meta.resyncs, vec![10]);
+    assert_eq!(meta.frames.len(), 475);
+}
@@ -0,0 +1,81 @@
+extern crate mp3_metadata;
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+/// Counts the bytes read.
+struct Counter {
+    inner: Cursor<Vec<u8>>,
+    read: usize,
+}
+
+impl Read for Counter {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read += read;
+        Ok(read)
+    }
+}
+
+impl Seek for Counter {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// ID3v2.4 tag with a footer, holding a title.
+fn appended_tag(title: &str) -> Vec<u8> {
+    let mut frame = b"TIT2".to_vec();
+    frame.extend_from_slice(&[0, 0, 0, title.len() as u8 + 1, 0, 0, 3]);
+    frame.extend_from_slice(title.as_bytes());
+    let size = [0, 0, 0, frame.len() as u8];
+    let mut tag = b"ID3\x04\x00\x10".to_vec();
+    tag.extend_from_slice(&size);
+    tag.extend_from_slice(&frame);
+    tag.extend_from_slice(b"3DI\x04\x00\x10");
+    tag.extend_from_slice(&size);
+    tag
+}
+
+#[test]
+fn same_as_metadata() {
+    for path in ["assets/test.mp3", "assets/id3v2.mp3"].iter() {
+        let meta = mp3_metadata::read_from_file(path).expect("read_from_file failed");
+        let tags = mp3_metadata::read_tags_from_file(path).expect("read_tags_from_file failed");
+        assert_eq!(tags.tag, meta.tag);
+        assert_eq!(tags.optional_info, meta.optional_info);
+        assert_eq!(tags.ape, meta.ape);
+        assert_eq!(tags.lyrics3, meta.lyrics3);
+    }
+}
+
+#[test]
+fn few_bytes_read() {
+    let buf = std::fs::read("assets/test.mp3").expect("read failed");
+    let mut counter = Counter {
+        inner: Cursor::new(buf),
+        read: 0,
+    };
+    let tags =
+        mp3_metadata::read_tags_from_reader(&mut counter).expect("read_tags_from_reader failed");
+    assert!(tags.tag.is_some());
+    assert!(counter.read <= 8192, "{} bytes read", counter.read);
+}
+
+#[test]
+fn appended_id3v2() {
+    let buf = std::fs::read("assets/test.mp3").expect("read failed");
+    // Between the audio and the ID3v1 tag.
+    let (audio, v1) = buf.split_at(buf.len() - 128);
+    let buf = [audio, &appended_tag("Appended"), v1].concat();
+
+    let tags = mp3_metadata::read_tags_from_slice(&buf).expect("read_tags_from_slice failed");
+    assert_eq!(tags.optional_info.len(), 1);
+    assert_eq!(tags.optional_info[0].title, Some("Appended".to_owned()));
+    assert_eq!(tags.optional_info[0].major_version, 4);
+    assert!(tags.tag.is_some());
+
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.optional_info.len(), 1);
+    assert_eq!(meta.optional_info[0].title, Some("Appended".to_owned()));
+    assert_eq!(meta.optional_info[0].position, meta.frames.len() as u32);
+}
@@ -15,8 +15,7 @@ fn truncate() {
         assert!(!frame.padding);
         assert!(!frame.private_bit);
         assert_eq!(frame.chan_type, mp3_metadata::ChannelType::SingleChannel);
-        assert!(!frame.intensity_stereo);
-        assert!(!frame.ms_stereo);
+        assert_eq!(frame.mode_extension, None);
         assert_eq!(frame.copyright, mp3_metadata::Copyright::None);
         assert_eq!(frame.status, mp3_metadata::Status::Copy);
         assert_eq!(frame.emphasis, mp3_metadata::Emphasis::None);
@@ -0,0 +1,190 @@
+extern crate mp3_metadata;
+
+use std::time::Duration;
+
+use mp3_metadata::VbrHeaderKind;
+
+/// MPEG-1 Layer III, 128 kb/s, 44100 Hz, mono, without CRC.
+fn xing_frame(tag: &[u8], frames: u32, bytes: u32) -> Vec<u8> {
+    let mut frame = vec![0; 417];
+    frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0xc0]);
+    let mut xing = tag.to_vec();
+    xing.extend(&0xfu32.to_be_bytes());
+    xing.extend(&frames.to_be_bytes());
+    xing.extend(&bytes.to_be_bytes());
+    xing.extend((0..100).map(|i| i * 2));
+    xing.extend(&50u32.to_be_bytes());
+    frame[21..21 + xing.len()].copy_from_slice(&xing);
+    frame
+}
+
+#[test]
+fn xing() {
+    let audio = std::fs::read("assets/test.mp3").expect("read failed");
+    let frames = mp3_metadata::read_from_slice(&audio)
+        .expect("read_from_slice failed")
+        .frames
+        .len() as u32;
+
+    let mut buf = xing_frame(b"Xing", frames, audio.len() as u32 + 417);
+    buf.extend(&audio);
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.frames.len() as u32, frames + 1);
+    // The Xing frame isn't part of the statistics, the header wins over the frames.
+    assert_eq!(meta.summary.frames, frames);
+    assert_eq!(meta.summary.bitrate_mode, mp3_metadata::BitrateMode::Vbr);
+    let header = meta.vbr_header.expect("no VBR header");
+    assert_eq!(header.offset, 0);
+    assert_eq!(header.sampling_freq, 44100);
+    assert_eq!(header.samples_per_frame, 1152);
+    assert_eq!(header.frames(), Some(frames));
+    assert_eq!(header.bytes(), Some(buf.len() as u32));
+    // 475 frames of 1152 samples at 44100 Hz.
+    assert_eq!(header.duration(), Some(Duration::new(12, 408163265)));
+    match header.kind {
+        VbrHeaderKind::Xing(xing) => {
+            assert!(!xing.info);
+            assert_eq!(xing.quality, Some(50));
+            assert_eq!(xing.toc.expect("no TOC")[99], 198);
+        }
+        VbrHeaderKind::Vbri(_) => panic!("expected a Xing header"),
+    }
+}
+
+#[test]
+fn info() {
+    let mut buf = xing_frame(b"Info", 1, 2);
+    buf.extend(std::fs::read("assets/test.mp3").expect("read failed"));
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    match meta.vbr_header.expect("no VBR header").kind {
+        VbrHeaderKind::Xing(xing) => assert!(xing.info),
+        VbrHeaderKind::Vbri(_) => panic!("expected a Xing header"),
+    }
+    assert_eq!(meta.summary.bitrate_mode, mp3_metadata::BitrateMode::Cbr);
+
+    let meta = mp3_metadata::read_from_file("assets/test.mp3").expect("read_from_file failed");
+    assert_eq!(meta.vbr_header, None);
+}
+
+fn crc16(buf: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for c in buf {
+        crc ^= *c as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xa001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+fn lame_frame(length: u32) -> Vec<u8> {
+    let mut frame = xing_frame(b"Info", 475, length);
+    // The LAME tag starts right after the 120 bytes of the Xing header.
+    let mut lame = b"LAME3.99r".to_vec();
+    lame.extend(&[0x04, 195]);
+    lame.extend(&0x0040_0000u32.to_be_bytes());
+    lame.extend(&[
+        0x2e, 0x41, 0, 0, 0x15, 128, 0x24, 0x04, 0xd2, 0x4c, 0xfe, 0x01, 0xf4,
+    ]);
+    lame.extend(&length.to_be_bytes());
+    lame.extend(&[0x12, 0x34]);
+    frame[141..141 + lame.len()].copy_from_slice(&lame);
+    let crc = crc16(&frame[..175]);
+    frame[175..177].copy_from_slice(&crc.to_be_bytes());
+    frame
+}
+
+#[test]
+fn lame() {
+    let audio = std::fs::read("assets/test.mp3").expect("read failed");
+    let mut buf = lame_frame(audio.len() as u32 + 417);
+    buf.extend(&audio);
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    let lame = meta.lame_tag().expect("no LAME tag");
+    assert_eq!(lame.encoder, "LAME3.99r");
+    assert_eq!(lame.revision, 0);
+    assert_eq!(lame.vbr_method, mp3_metadata::LameVbrMethod::VbrMtrh);
+    assert_eq!(lame.lowpass, 19500);
+    assert_eq!(lame.peak(), 0.5);
+    assert_eq!(
+        lame.track_gain,
+        Some(mp3_metadata::ReplayGain {
+            originator: 3,
+            adjustment: -65,
+        })
+    );
+    assert_eq!(lame.track_gain.map(|gain| gain.db()), Some(-6.5));
+    assert_eq!(lame.album_gain, None);
+    assert_eq!(lame.encoding_flags, 1);
+    assert_eq!(lame.ath_type, 5);
+    assert_eq!(lame.bitrate, 128);
+    assert_eq!(lame.encoder_delay, 576);
+    assert_eq!(lame.padding, 1234);
+    assert_eq!(lame.noise_shaping, 0);
+    assert_eq!(lame.stereo_mode, 3);
+    assert!(!lame.unwise_settings);
+    assert_eq!(lame.source_sample_rate, 1);
+    assert_eq!(lame.mp3_gain, -2);
+    assert_eq!(lame.preset, 500);
+    assert_eq!(lame.music_length, buf.len() as u32);
+    assert_eq!(lame.music_crc, 0x1234);
+    assert!(lame.crc_valid);
+    assert_eq!(meta.summary.bitrate_mode, mp3_metadata::BitrateMode::Vbr);
+    // The Info frame itself, the encoder delay and the padding are not part of the audio.
+    assert_eq!(
+        meta.gapless_info().map(|info| info.source),
+        Some(mp3_metadata::GaplessSource::Lame)
+    );
+    assert_eq!(meta.gapless_samples(), Some(475 * 1152 - 576 - 1234));
+    assert_eq!(meta.gapless_duration(), Some(Duration::new(12, 367120181)));
+
+    // Corrupt the encoder string.
+    buf[149] = b'X';
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert!(!meta.lame_tag().expect("no LAME tag").crc_valid);
+}
+
+#[test]
+fn vbri() {
+    let audio = std::fs::read("assets/test.mp3").expect("read failed");
+    let mut frame = vec![0; 417];
+    frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0xc0]);
+    let mut vbri = b"VBRI".to_vec();
+    vbri.extend(&[0, 1, 0x04, 0xb0, 0, 75]);
+    vbri.extend(&(audio.len() as u32 + 417).to_be_bytes());
+    vbri.extend(&475u32.to_be_bytes());
+    // 3 entries of 2 bytes, with a scale of 4.
+    vbri.extend(&[0, 3, 0, 4, 0, 2, 0, 160]);
+    vbri.extend(&[0x1, 0x00, 0x2, 0x00, 0x0, 0x10]);
+    frame[36..36 + vbri.len()].copy_from_slice(&vbri);
+
+    let mut buf = frame;
+    buf.extend(&audio);
+    let meta = mp3_metadata::read_from_slice(&buf).expect("read_from_slice failed");
+    assert_eq!(meta.lame_tag(), None);
+    let header = meta.vbr_header.expect("no VBR header");
+    assert_eq!(header.frames(), Some(475));
+    assert_eq!(header.bytes(), Some(buf.len() as u32));
+    assert_eq!(header.duration(), Some(Duration::new(12, 408163265)));
+    match header.kind {
+        VbrHeaderKind::Vbri(vbri) => {
+            assert_eq!(
+                vbri,
+                mp3_metadata::VbriHeader {
+                    version: 1,
+                    delay: 1200,
+                    quality: 75,
+                    bytes: buf.len() as u32,
+                    frames: 475,
+                    frames_per_entry: 160,
+                    toc: vec![1024, 2048, 64],
+                }
+            );
+        }
+        VbrHeaderKind::Xing(_) => panic!("expected a VBRI header"),
+    }
+}